@@ -0,0 +1,20 @@
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rustdoc_denormalize::Crate;
+
+/// Time `Crate::from_str` over the bundled dumps. `alloc` is the small
+/// fixture, `std` the large one.
+fn from_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_str");
+    group.sample_size(10);
+    for name in ["alloc", "std"] {
+        let path = format!("{}/assets/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        let json = fs::read_to_string(&path).unwrap();
+        group.bench_function(name, |b| b.iter(|| Crate::from_str(&json).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, from_str);
+criterion_main!(benches);
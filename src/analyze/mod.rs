@@ -1,21 +1,30 @@
-use rustdoc_denormalize::Item;
+//! Analyses over denormalized items
 
-// Most items in the stdlib can be const probably. It's mainly not things which
-// touch host APIs, globals, or directly allocate on the heap. Though the heap
-// ones we can probably overcome eventually, so for now we're counting them.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::{Crate, Item};
+
+/// Count how many stable items are const, returning `(count, excluded)`.
+///
+/// Most items in the stdlib can be const probably. It's mainly not things which
+/// touch host APIs, globals, or directly allocate on the heap. Though the heap
+/// ones we can probably overcome eventually, so for now we're counting them.
 pub fn count_const_items(items: &[Item]) -> (usize, usize) {
     let exclude_paths = &["std::os", "std::fs", "std::net", "std::process"];
-    let should_exclude = |item: &&Item| false;
+    let should_exclude = |_item: &&Item| false;
     let count_current = |item: &&Item| item.is_const;
     count_items(items, exclude_paths, should_exclude, count_current)
 }
 
-// Async items are a bit trickier. We probably don't want async ops. But we
-// do want to count every single generic param. But also make sure we include
-// all of net, fs, and most traits + trait impls.
+/// Count how many stable items are async, returning `(count, excluded)`.
+///
+/// Async items are a bit trickier. We probably don't want async ops. But we
+/// do want to count every single generic param. But also make sure we include
+/// all of net, fs, and most traits + trait impls.
 pub fn count_async_items(
     items: &[Item],
-    mut should_exclude: impl FnMut(&&Item) -> bool,
+    should_exclude: impl FnMut(&&Item) -> bool,
 ) -> (usize, usize) {
     let exclude_paths = &[
         "core::ops",
@@ -35,6 +44,131 @@ pub fn count_async_items(
     count_items(items, exclude_paths, should_exclude, count_current)
 }
 
+/// Count the functions whose outermost return type is a `Result`.
+pub fn count_result_returning(functions: &[Item]) -> usize {
+    count_returning(functions, "Result")
+}
+
+/// Count the functions whose outermost return type is an `Option`.
+pub fn count_option_returning(functions: &[Item]) -> usize {
+    count_returning(functions, "Option")
+}
+
+/// Count the impls on types defined in the crate vs on external types,
+/// returning `(local, external)`.
+///
+/// An impl is local if its self type, or the type behind a reference to it,
+/// is one of the structs, enums or unions collected in the crate.
+pub fn impl_locality(krate: &Crate) -> (usize, usize) {
+    let local = local_type_ids(krate);
+    let local_count = krate
+        .impls
+        .iter()
+        .filter(|item| {
+            item.for_type_id
+                .as_ref()
+                .is_some_and(|id| local.contains(id.as_str()))
+        })
+        .count();
+    (local_count, krate.impls.len() - local_count)
+}
+
+/// The IDs of the structs, enums and unions collected in the crate.
+fn local_type_ids(krate: &Crate) -> BTreeSet<&str> {
+    krate
+        .structs
+        .iter()
+        .chain(&krate.enums)
+        .chain(&krate.unions)
+        .map(|item| item.id.as_str())
+        .collect()
+}
+
+/// A range of trait impl counts, used by [`traits_per_type_histogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bucket {
+    /// No trait impls.
+    Zero,
+    /// Between 1 and 3 trait impls.
+    OneToThree,
+    /// Between 4 and 10 trait impls.
+    FourToTen,
+    /// 11 or more trait impls.
+    MoreThanTen,
+}
+
+impl Bucket {
+    /// The bucket a given number of trait impls falls into.
+    pub fn from_count(count: usize) -> Self {
+        match count {
+            0 => Self::Zero,
+            1..=3 => Self::OneToThree,
+            4..=10 => Self::FourToTen,
+            _ => Self::MoreThanTen,
+        }
+    }
+}
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::Zero => "0",
+            Self::OneToThree => "1-3",
+            Self::FourToTen => "4-10",
+            Self::MoreThanTen => "11+",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Count how many types implement a given number of traits, bucketed.
+///
+/// Impls are grouped by the id of their self type, like [`impl_locality`],
+/// so only impls on the crate's structs, enums and unions are counted. Types
+/// without any impls are counted in [`Bucket::Zero`].
+pub fn traits_per_type_histogram(krate: &Crate) -> BTreeMap<Bucket, usize> {
+    let mut counts: BTreeMap<&str, usize> = local_type_ids(krate)
+        .into_iter()
+        .map(|id| (id, 0))
+        .collect();
+    for item in &krate.impls {
+        let count = item
+            .for_type_id
+            .as_ref()
+            .and_then(|id| counts.get_mut(id.as_str()));
+        if let Some(count) = count {
+            *count += 1;
+        }
+    }
+
+    let mut histogram = BTreeMap::new();
+    for count in counts.into_values() {
+        *histogram.entry(Bucket::from_count(count)).or_default() += 1;
+    }
+    histogram
+}
+
+/// The deepest nesting of generic args across all items.
+pub fn max_generic_depth<'a>(items: impl IntoIterator<Item = &'a Item>) -> usize {
+    items
+        .into_iter()
+        .map(|item| item.max_generic_depth)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Count the functions which never return, meaning they return `!`.
+pub fn count_never_returning(functions: &[Item]) -> usize {
+    functions.iter().filter(|item| item.never_returns).count()
+}
+
+fn count_returning(functions: &[Item], name: &str) -> usize {
+    functions
+        .iter()
+        .filter(|item| item.return_type.as_deref() == Some(name))
+        .count()
+}
+
 fn count_items(
     items: &[Item],
     exclude_paths: &[&str],
@@ -46,13 +180,10 @@ fn count_items(
         .iter()
         .filter(|item| item.stability.is_stable())
         .filter(|item| {
-            if should_exclude_path(&item.path, exclude_paths) {
-                excluded += 1;
-                false
-            } else if should_exclude_path(&item.target_trait, exclude_paths) {
-                excluded += 1;
-                false
-            } else if should_exclude(item) {
+            if should_exclude_path(&item.path, exclude_paths)
+                || should_exclude_path(&item.target_trait, exclude_paths)
+                || should_exclude(item)
+            {
                 excluded += 1;
                 false
             } else {
@@ -71,10 +202,7 @@ fn should_exclude_path(target: &str, exclude_paths: &[&str]) -> bool {
         if should_exclude {
             true
         } else {
-            match target.starts_with(path) {
-                true => true,
-                false => false,
-            }
+            target.starts_with(path)
         }
     });
     // println!("{out}");
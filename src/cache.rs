@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::item::Fnv;
+use crate::{Crate, Options, ParseError, SCHEMA_VERSION};
+
+/// A denormalized crate stored next to the rustdoc JSON it was parsed from.
+///
+/// The cache is only valid when the input file, the rendering options, the
+/// denormalized format, and the rustdoc format this library reads are
+/// unchanged.
+#[derive(Serialize, Deserialize)]
+struct Cache {
+    schema_version: u32,
+    /// `rustdoc_types::FORMAT_VERSION` of the library which wrote the cache
+    native_format_version: u32,
+    input_hash: u64,
+    options: Options,
+    krate: Crate,
+}
+
+impl Cache {
+    fn is_valid(&self, input_hash: u64, options: &Options) -> bool {
+        self.schema_version == SCHEMA_VERSION
+            && self.native_format_version == rustdoc_types::FORMAT_VERSION
+            && self.input_hash == input_hash
+            && &self.options == options
+    }
+}
+
+/// Where the cache for a rustdoc JSON file lives, e.g. `std.json.cache`.
+pub(crate) fn cache_path(path: &Path) -> PathBuf {
+    let mut cache = path.as_os_str().to_owned();
+    cache.push(".cache");
+    cache.into()
+}
+
+pub(crate) fn load(path: &Path, options: Options) -> Result<Crate, ParseError> {
+    let input = fs::read(path)?;
+    let mut hasher = Fnv::new();
+    hasher.write(&input);
+    let input_hash = hasher.finish();
+
+    let cache_path = cache_path(path);
+    // A missing or unreadable cache is treated as stale.
+    if let Ok(cache) = fs::read(&cache_path) {
+        if let Ok(cache) = serde_json::from_slice::<Cache>(&cache) {
+            if cache.is_valid(input_hash, &options) {
+                return Ok(cache.krate);
+            }
+        }
+    }
+
+    let krate = Crate::from_reader_with_options(&input[..], options.clone())?;
+    let cache = Cache {
+        schema_version: SCHEMA_VERSION,
+        native_format_version: rustdoc_types::FORMAT_VERSION,
+        input_hash,
+        options,
+        krate,
+    };
+    // The crate is parsed either way, so failing to cache it isn't an error.
+    if let Ok(json) = serde_json::to_vec(&cache) {
+        let _ = fs::write(&cache_path, json);
+    }
+    Ok(cache.krate)
+}
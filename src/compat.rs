@@ -0,0 +1,38 @@
+use serde_json::Value;
+
+/// The format version `rustdoc_types` reads natively.
+const NATIVE_VERSION: u64 = rustdoc_types::FORMAT_VERSION as u64;
+
+/// Rewrite rustdoc JSON from a newer format version into the shape of the
+/// version `rustdoc_types` understands, so a range of nightlies can be read.
+///
+/// Versions without any known fixups are left as-is, and deserialization
+/// decides whether they're compatible.
+pub(crate) fn normalize(krate: &mut Value) {
+    let version = match krate["format_version"].as_u64() {
+        Some(version) => version,
+        None => return,
+    };
+
+    // Version 25 renamed `Typedef` to `TypeAlias`
+    if version >= 25 {
+        rename_kinds(krate, "type_alias", "typedef");
+    }
+
+    if version > NATIVE_VERSION {
+        krate["format_version"] = Value::from(NATIVE_VERSION);
+    }
+}
+
+/// Rename an item kind, both on the items in the index and in the paths.
+fn rename_kinds(krate: &mut Value, from: &str, to: &str) {
+    for table in ["index", "paths"] {
+        if let Some(entries) = krate[table].as_object_mut() {
+            for entry in entries.values_mut() {
+                if entry["kind"] == from {
+                    entry["kind"] = Value::from(to);
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,40 @@
+use std::cell::RefCell;
+
 use rustdoc_types::ItemEnum;
 
+use crate::{Options, Warning};
+
 /// Internal rustdoc database structure with various query methods on it.
 pub(crate) struct Database {
     inner: rustdoc_types::Crate,
+    options: Options,
+    /// Problems found while formatting, which the formatters can't return
+    warnings: RefCell<Vec<Warning>>,
 }
 
 impl Database {
     /// Create a new instance of database
-    pub(crate) fn new(inner: rustdoc_types::Crate) -> Self {
-        Self { inner }
+    pub(crate) fn new(inner: rustdoc_types::Crate, options: Options) -> Self {
+        Self {
+            inner,
+            options,
+            warnings: RefCell::new(vec![]),
+        }
+    }
+
+    /// Record a problem found while formatting an item
+    pub(crate) fn warn(&self, warning: Warning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Take the problems recorded while formatting
+    pub(crate) fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
+    }
+
+    /// The options used to render items
+    pub(crate) fn options(&self) -> &Options {
+        &self.options
     }
 
     /// Find a rustdoc `Item` by id
@@ -23,20 +49,43 @@ impl Database {
         Some(summary.path.join("::"))
     }
 
-    /// Get a list of all modules
-    pub(crate) fn modules(&self) -> Vec<(String, rustdoc_types::Module)> {
-        let mut out: Vec<_> = self
+    /// Get a list of all modules reachable from the crate root, building their
+    /// paths from the module names while walking down the tree. Re-exported
+    /// modules are skipped so every module is visited once, under its
+    /// defining path.
+    pub(crate) fn modules_recursive(&self) -> Vec<(String, &rustdoc_types::Module)> {
+        fn walk<'a>(
+            db: &'a Database,
+            id: &rustdoc_types::Id,
+            path: String,
+            out: &mut Vec<(String, &'a rustdoc_types::Module)>,
+        ) {
+            let module = match db.inner.index.get(id).map(|item| &item.inner) {
+                Some(ItemEnum::Module(module)) => module,
+                _ => return,
+            };
+            out.push((path.clone(), module));
+            for child in &module.items {
+                if let Some(item) = db.inner.index.get(child) {
+                    if let (ItemEnum::Module(_), Some(name)) = (&item.inner, &item.name) {
+                        walk(db, child, format!("{path}::{name}"), out);
+                    }
+                }
+            }
+        }
+
+        let root = &self.inner.root;
+        let name = match self
             .inner
             .index
-            .iter()
-            .filter_map(|(id, item)| match &item.inner {
-                ItemEnum::Module(module) => {
-                    let path = self.find_path(id)?;
-                    Some((path, module.clone()))
-                }
-                _ => None,
-            })
-            .collect();
+            .get(root)
+            .and_then(|item| item.name.clone())
+        {
+            Some(name) => name,
+            None => return vec![],
+        };
+        let mut out = vec![];
+        walk(self, root, name, &mut out);
         out.sort_by(|(left, _), (right, _)| left.cmp(right));
         out
     }
@@ -58,9 +107,7 @@ impl Database {
                 _ => None,
             })
         }
-        ids.into_iter()
-            .filter_map(|id| find_trait(self, id))
-            .collect()
+        ids.iter().filter_map(|id| find_trait(self, id)).collect()
     }
 
     pub(crate) fn find_functions(
@@ -77,7 +124,7 @@ impl Database {
                 _ => None,
             })
         }
-        ids.into_iter()
+        ids.iter()
             .filter_map(|id| find_function(self, id))
             .collect()
     }
@@ -96,9 +143,7 @@ impl Database {
                 _ => None,
             })
         }
-        ids.into_iter()
-            .filter_map(|id| find_struct(self, id))
-            .collect()
+        ids.iter().filter_map(|id| find_struct(self, id)).collect()
     }
 
     pub(crate) fn find_enums(
@@ -115,9 +160,7 @@ impl Database {
                 _ => None,
             })
         }
-        ids.into_iter()
-            .filter_map(|id| find_enum(self, id))
-            .collect()
+        ids.iter().filter_map(|id| find_enum(self, id)).collect()
     }
 
     pub(crate) fn find_impls(
@@ -134,8 +177,151 @@ impl Database {
                 _ => None,
             })
         }
-        ids.into_iter()
-            .filter_map(|id| find_impl(self, id))
+        ids.iter().filter_map(|id| find_impl(self, id)).collect()
+    }
+
+    pub(crate) fn find_variants(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Variant)> {
+        fn find_variant(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Variant)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Variant(variant) => Some((item, variant)),
+                ItemEnum::Import(import) => find_variant(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter().filter_map(|id| find_variant(self, id)).collect()
+    }
+
+    pub(crate) fn find_fields(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Type)> {
+        ids.iter()
+            .filter_map(|id| self.find_item(id))
+            .filter_map(|item| match item.clone().inner {
+                ItemEnum::StructField(ty) => Some((item, ty)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) fn find_primitives(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Primitive)> {
+        fn find_primitive(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Primitive)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Primitive(primitive) => Some((item, primitive)),
+                ItemEnum::Import(import) => find_primitive(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter()
+            .filter_map(|id| find_primitive(self, id))
             .collect()
     }
+
+    pub(crate) fn find_type_aliases(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Typedef)> {
+        fn find_type_alias(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Typedef)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Typedef(alias) => Some((item, alias)),
+                ItemEnum::Import(import) => find_type_alias(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter()
+            .filter_map(|id| find_type_alias(self, id))
+            .collect()
+    }
+
+    pub(crate) fn find_assoc_items(&self, ids: &[rustdoc_types::Id]) -> Vec<rustdoc_types::Item> {
+        ids.iter()
+            .filter_map(|id| self.find_item(id))
+            .filter(|item| {
+                matches!(
+                    item.inner,
+                    ItemEnum::AssocConst { .. } | ItemEnum::AssocType { .. }
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn find_unions(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Union)> {
+        fn find_union(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Union)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Union(union) => Some((item, union)),
+                ItemEnum::Import(import) => find_union(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter().filter_map(|id| find_union(self, id)).collect()
+    }
+
+    pub(crate) fn find_constants(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Constant)> {
+        fn find_constant(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Constant)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Constant(constant) => Some((item, constant)),
+                ItemEnum::Import(import) => find_constant(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter()
+            .filter_map(|id| find_constant(self, id))
+            .collect()
+    }
+
+    pub(crate) fn find_statics(
+        &self,
+        ids: &[rustdoc_types::Id],
+    ) -> Vec<(rustdoc_types::Item, rustdoc_types::Static)> {
+        fn find_static(
+            db: &Database,
+            id: &rustdoc_types::Id,
+        ) -> Option<(rustdoc_types::Item, rustdoc_types::Static)> {
+            db.find_item(id).and_then(|item| match item.clone().inner {
+                ItemEnum::Static(static_) => Some((item, static_)),
+                ItemEnum::Import(import) => find_static(db, &import.id?),
+                _ => None,
+            })
+        }
+        ids.iter().filter_map(|id| find_static(self, id)).collect()
+    }
+
+    /// Given a list of IDs, find all declarative and proc macros.
+    pub(crate) fn find_macros(&self, ids: &[rustdoc_types::Id]) -> Vec<rustdoc_types::Item> {
+        fn find_macro(db: &Database, id: &rustdoc_types::Id) -> Option<rustdoc_types::Item> {
+            db.find_item(id).and_then(|item| match &item.inner {
+                ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => Some(item),
+                ItemEnum::Import(import) => find_macro(db, import.id.as_ref()?),
+                _ => None,
+            })
+        }
+        ids.iter().filter_map(|id| find_macro(self, id)).collect()
+    }
 }
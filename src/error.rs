@@ -0,0 +1,41 @@
+use std::{error, fmt, io};
+
+/// An error returned when a rustdoc dump can't be read.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The input wasn't valid rustdoc JSON
+    Json(serde_json::Error),
+    /// The input or its cache couldn't be read or written
+    Io(io::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid rustdoc JSON: {err}"),
+            Self::Io(err) => write!(f, "could not access rustdoc JSON: {err}"),
+        }
+    }
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
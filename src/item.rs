@@ -1,11 +1,12 @@
-use super::Stability;
-use serde::{Deserialize, Serialize};
+use super::{format_ident, Stability, Visibility};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// A trait
-#[derive(Debug, PartialEq, PartialOrd, Ord, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Default, Serialize, Deserialize)]
 pub struct Item {
     /// What kind of item is this?
-    pub kind: &'static str,
+    #[serde(deserialize_with = "deserialize_kind")]
+    pub kind: Kind,
     /// The rustdoc ID assigned to this item
     pub id: String,
     /// The name
@@ -14,6 +15,11 @@ pub struct Item {
     pub path: String,
     /// If we're implementing a trait, which trait is it?
     pub target_trait: String,
+    /// If this is an impl, which type is it for?
+    pub for_type: String,
+    /// If this is an impl on a path type, or a reference to one, the rustdoc
+    /// ID of that type
+    pub for_type_id: Option<String>,
     /// The signature of the item
     pub decl: String,
     /// Does this item have generics?
@@ -22,8 +28,201 @@ pub struct Item {
     pub is_const: bool,
     /// Is this an async item?
     pub is_async: bool,
+    /// Is this an auto trait, like `Send`?
+    pub is_auto: bool,
+    /// Is this an unsafe trait, impl, or function?
+    pub is_unsafe: bool,
     /// What is the stability of this item?
     pub stability: Stability,
+    /// The feature gate from the item's stability attribute, e.g. `rust1`
+    pub feature: Option<String>,
+    /// For unstable items, the number of the tracking issue
+    pub issue: Option<String>,
+    /// For stable items, the version they were stabilized in
+    pub since: Option<String>,
     /// How many methods does this item have?
     pub fn_count: usize,
+    /// For traits, how many of the methods have a default body?
+    pub provided_fn_count: usize,
+    /// For traits, how many associated consts and types are declared?
+    pub assoc_count: usize,
+    /// For impls, how many associated consts are defined?
+    pub assoc_const_count: usize,
+    /// For structs and unions, how many fields are there?
+    pub field_count: usize,
+    /// For enums, how many variants are there?
+    pub variant_count: usize,
+    /// For impls, are lifetimes the only generic params? These don't count
+    /// towards `has_generics`.
+    pub lifetime_only_generics: bool,
+    /// How deeply generic args are nested in this item's types, e.g. 3 for
+    /// `Vec<Option<Box<T>>>`
+    pub max_generic_depth: usize,
+    /// For functions, the name of the outermost return type, e.g. `Result`
+    /// for `-> Result<T, E>`
+    pub return_type: Option<String>,
+    /// For functions, does it return `!`?
+    pub never_returns: bool,
+    /// Is this item hidden from the docs with `#[doc(hidden)]`?
+    pub is_hidden: bool,
+    /// Does this item have a doc comment?
+    pub is_documented: bool,
+    /// The first line of the item's docs
+    pub doc_summary: Option<String>,
+    /// The first paragraph of the item's docs
+    pub docs: Option<String>,
+    /// For impls, was this generated by `#[derive(..)]`?
+    pub derived: bool,
+    /// Is this item deprecated?
+    pub is_deprecated: bool,
+    /// Who can access this item?
+    pub visibility: Visibility,
+}
+
+impl Item {
+    /// A hash of the item's API, for cheaply detecting changes between runs.
+    ///
+    /// This covers the kind, path, name, and signature of the item. Counts
+    /// like `fn_count` depend on where the item was found, so they're left
+    /// out. The hash uses FNV-1a, so it stays the same across Rust versions.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv::new();
+        hasher.write_field(self.kind.as_bytes());
+        hasher.write_field(self.path.as_bytes());
+        hasher.write_field(self.name.as_bytes());
+        hasher.write_field(self.decl.as_bytes());
+        hasher.finish()
+    }
+
+    /// For traits, how many methods have to be implemented.
+    pub fn required_fn_count(&self) -> usize {
+        self.fn_count.saturating_sub(self.provided_fn_count)
+    }
+
+    /// The shape of the item's signature: its `decl` without the item's name
+    /// or body, e.g. `fn() -> Self` for `fn new() -> Self { .. }`.
+    ///
+    /// Impls are named after their trait, which is part of their shape, so
+    /// their `decl` is kept whole.
+    pub fn signature(&self) -> String {
+        let decl = self.decl.trim_end_matches(" { .. }").trim_end_matches(';');
+        match self.kind {
+            "impl" => decl.to_string(),
+            _ if self.name.is_empty() => decl.to_string(),
+            // Keyword names are written as raw identifiers in the `decl`
+            _ => decl.replacen(&format!(" {}", format_ident(&self.name)), "", 1),
+        }
+    }
+}
+
+/// The kind of an item. Spelled through an alias so serde doesn't try to
+/// borrow it from the input, which would tie deserialization to `'static` data.
+pub(crate) type Kind = &'static str;
+
+/// The kinds of items the parser produces.
+const KINDS: [&str; 12] = [
+    "trait",
+    "struct",
+    "enum",
+    "union",
+    "impl",
+    "function",
+    "type",
+    "const",
+    "static",
+    "macro",
+    "proc_attribute",
+    "proc_derive",
+];
+
+/// Map a deserialized kind back onto its `&'static str`, so items can be read
+/// from owned input like a file.
+pub(crate) fn deserialize_kind<'de, D>(deserializer: D) -> Result<&'static str, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let kind = String::deserialize(deserializer)?;
+    KINDS
+        .iter()
+        .find(|known| **known == kind)
+        .copied()
+        .ok_or_else(|| serde::de::Error::unknown_variant(&kind, &KINDS))
+}
+
+/// A minimal FNV-1a hasher. Unlike `DefaultHasher` its output is specified,
+/// which matters for hashes which get stored.
+#[derive(Debug)]
+pub(crate) struct Fnv(u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    /// Write a field followed by a terminator, so `("ab", "c")` and
+    /// `("a", "bc")` hash differently.
+    pub(crate) fn write_field(&mut self, bytes: &[u8]) {
+        self.write(bytes);
+        self.write(&[0xff]);
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// An owned item of any kind, used when iterating a [`Crate`] as a single
+/// collection rather than per-kind vectors.
+///
+/// [`Crate`]: super::Crate
+#[derive(Debug, PartialEq, PartialOrd, Ord, Eq)]
+pub enum AnyItem {
+    /// A trait
+    Trait(Item),
+    /// A struct
+    Struct(Item),
+    /// An enum
+    Enum(Item),
+    /// A trait impl
+    Impl(Item),
+    /// A function or method
+    Function(Item),
+    /// A declarative or proc macro
+    Macro(Item),
+    /// A static
+    Static(Item),
+    /// A free constant
+    Constant(Item),
+    /// A union
+    Union(Item),
+    /// A type alias
+    TypeAlias(Item),
+    /// An associated const or type
+    AssocItem(Item),
+}
+
+impl AnyItem {
+    /// Access the underlying item
+    pub fn item(&self) -> &Item {
+        match self {
+            Self::Trait(item)
+            | Self::Struct(item)
+            | Self::Enum(item)
+            | Self::Impl(item)
+            | Self::Function(item)
+            | Self::TypeAlias(item)
+            | Self::AssocItem(item)
+            | Self::Union(item)
+            | Self::Constant(item)
+            | Self::Static(item)
+            | Self::Macro(item) => item,
+        }
+    }
 }
@@ -4,23 +4,43 @@
 #![deny(missing_debug_implementations, nonstandard_style)]
 #![warn(missing_docs, future_incompatible, unreachable_pub)]
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::io;
+use std::path::Path;
 
 use cli_table::TableStruct;
 use rustdoc_types::{
-    GenericBound, GenericParamDefKind, Term, TraitBoundModifier, Type, WherePredicate,
+    Abi, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, ItemEnum, Term,
+    TraitBoundModifier, Type, TypeBindingKind, WherePredicate,
 };
 use serde::{Deserialize, Serialize};
 
+pub mod analyze;
+mod cache;
+mod compat;
 mod database;
+mod error;
 mod item;
+mod options;
+mod sqlite;
+mod stats;
 mod table;
+mod warning;
 
 use database::Database;
-pub use item::Item;
+pub use error::ParseError;
+pub use item::{AnyItem, Item};
+pub use options::Options;
+pub use stats::{CrateStats, Stats, VisibilityStats};
+pub use warning::Warning;
+
+/// The version of the denormalized JSON format. This is bumped whenever the
+/// fields of an item change in a way that could break consumers, or when a
+/// new kind of item is collected.
+pub const SCHEMA_VERSION: u32 = 15;
 
 /// A crate
-#[derive(Debug, PartialEq, PartialOrd, Default)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
 pub struct Crate {
     /// Traits contained in this crate
     pub traits: Vec<item::Item>,
@@ -32,14 +52,69 @@ pub struct Crate {
     pub impls: Vec<item::Item>,
     /// Functions and methods contained in this crate
     pub functions: Vec<item::Item>,
+    /// Type aliases contained in this crate
+    pub type_aliases: Vec<item::Item>,
+    /// Associated consts and types declared in inherent impls
+    pub assoc_items: Vec<item::Item>,
+    /// Unions contained in this crate
+    pub unions: Vec<item::Item>,
+    /// Free constants contained in this crate
+    pub constants: Vec<item::Item>,
+    /// Statics contained in this crate
+    pub statics: Vec<item::Item>,
+    /// Declarative and proc macros contained in this crate
+    pub macros: Vec<item::Item>,
+    /// Problems encountered while parsing this crate
+    pub warnings: Vec<Warning>,
 }
 
 impl Crate {
     /// Create a new instance from a string slice.
-    pub fn from_str(s: &str) -> io::Result<Self> {
-        let krate: rustdoc_types::Crate = serde_json::from_str(&s)?;
-        let db = Database::new(krate);
-        let modules = db.modules();
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Result<Self, ParseError> {
+        Self::from_str_with_options(s, Options::default())
+    }
+
+    /// Create a new instance from a string slice, rendering items using the
+    /// provided options.
+    pub fn from_str_with_options(s: &str, options: Options) -> Result<Self, ParseError> {
+        Self::from_reader_with_options(s.as_bytes(), options)
+    }
+
+    /// Create a new instance from a rustdoc JSON file, reusing the
+    /// denormalized output cached at `<path>.cache` when the file hasn't
+    /// changed since.
+    pub fn from_path_cached(path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        Self::from_path_cached_with_options(path, Options::default())
+    }
+
+    /// Like `from_path_cached`, rendering items using the provided options.
+    /// Changing the options invalidates the cache.
+    pub fn from_path_cached_with_options(
+        path: impl AsRef<Path>,
+        options: Options,
+    ) -> Result<Self, ParseError> {
+        cache::load(path.as_ref(), options)
+    }
+
+    /// Create a new instance from a reader.
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self, ParseError> {
+        Self::from_reader_with_options(reader, Options::default())
+    }
+
+    /// Create a new instance from a reader, rendering items using the
+    /// provided options.
+    pub fn from_reader_with_options<R: io::Read>(
+        reader: R,
+        options: Options,
+    ) -> Result<Self, ParseError> {
+        // The format version comes last in rustdoc's output, so the input
+        // is read into a `Value` to check it before converting.
+        let mut krate: serde_json::Value = serde_json::from_reader(reader)?;
+        compat::normalize(&mut krate);
+        let krate: rustdoc_types::Crate = serde_json::from_value(krate)?;
+        let db = Database::new(krate, options);
+        let modules = db.modules_recursive();
 
         let mut output = Self {
             traits: vec![],
@@ -47,6 +122,13 @@ impl Crate {
             enums: vec![],
             impls: vec![],
             functions: vec![],
+            type_aliases: vec![],
+            assoc_items: vec![],
+            unions: vec![],
+            constants: vec![],
+            statics: vec![],
+            macros: vec![],
+            warnings: vec![],
         };
 
         for (path_name, module) in modules {
@@ -55,7 +137,14 @@ impl Crate {
             output.count_functions(&db, items, &path_name, false);
             output.parse_structs(&db, items, &path_name);
             output.parse_enums(&db, items, &path_name);
+            output.parse_unions(&db, items, &path_name);
+            output.parse_primitives(&db, items, &path_name);
+            output.parse_type_aliases(&db, items, &path_name);
+            output.parse_constants(&db, items, &path_name);
+            output.parse_statics(&db, items, &path_name);
+            output.parse_macros(&db, items, &path_name);
         }
+        output.warnings.append(&mut db.take_warnings());
 
         // NOTE(yosh): okay, so this whole section is super annoying, but in
         // order to filter data later we need to know the exact path of the
@@ -70,7 +159,7 @@ impl Crate {
         let traits: std::collections::HashMap<_, _> = output
             .traits
             .iter()
-            .map(|trait_| (trait_.id.clone(), trait_.clone()))
+            .map(|trait_| (trait_.id.clone(), trait_))
             .collect();
         for impl_ in output.impls.iter_mut() {
             let target_trait = match traits.get(&impl_.target_trait) {
@@ -91,6 +180,18 @@ impl Crate {
         output.impls.dedup_by_key(|t| t.id.clone());
         output.functions.sort();
         output.functions.dedup_by_key(|t| t.id.clone());
+        output.macros.sort();
+        output.macros.dedup_by_key(|t| t.id.clone());
+        output.statics.sort();
+        output.statics.dedup_by_key(|t| t.id.clone());
+        output.constants.sort();
+        output.constants.dedup_by_key(|t| t.id.clone());
+        output.unions.sort();
+        output.unions.dedup_by_key(|t| t.id.clone());
+        output.assoc_items.sort();
+        output.assoc_items.dedup_by_key(|t| t.id.clone());
+        output.type_aliases.sort();
+        output.type_aliases.dedup_by_key(|t| t.id.clone());
 
         Ok(output)
     }
@@ -100,7 +201,373 @@ impl Crate {
         self.traits.append(&mut other.traits);
         self.structs.append(&mut other.structs);
         self.enums.append(&mut other.enums);
+        self.impls.append(&mut other.impls);
         self.functions.append(&mut other.functions);
+        self.macros.append(&mut other.macros);
+        self.statics.append(&mut other.statics);
+        self.constants.append(&mut other.constants);
+        self.unions.append(&mut other.unions);
+        self.assoc_items.append(&mut other.assoc_items);
+        self.type_aliases.append(&mut other.type_aliases);
+        self.warnings.append(&mut other.warnings);
+    }
+
+    /// Move all items from `other` into `self`, keeping every collection sorted
+    /// and removing duplicate items as we go, leaving `other` empty.
+    ///
+    /// Both crates are expected to be sorted already, which is the case for
+    /// crates created through `from_str`. Unlike calling `append` followed by
+    /// a sort of the combined collections, this only needs a single linear
+    /// merge per collection, which makes it cheaper to fold many crates
+    /// together one at a time.
+    pub fn append_dedup(&mut self, other: &mut Self) {
+        merge_dedup(&mut self.traits, &mut other.traits);
+        merge_dedup(&mut self.structs, &mut other.structs);
+        merge_dedup(&mut self.enums, &mut other.enums);
+        merge_dedup(&mut self.impls, &mut other.impls);
+        merge_dedup(&mut self.functions, &mut other.functions);
+        merge_dedup(&mut self.macros, &mut other.macros);
+        merge_dedup(&mut self.statics, &mut other.statics);
+        merge_dedup(&mut self.constants, &mut other.constants);
+        merge_dedup(&mut self.unions, &mut other.unions);
+        merge_dedup(&mut self.assoc_items, &mut other.assoc_items);
+        merge_dedup(&mut self.type_aliases, &mut other.type_aliases);
+        self.warnings.append(&mut other.warnings);
+    }
+
+    /// The stable supported surface of the crate: only the items which are
+    /// stable, not hidden from the docs, and not deprecated.
+    pub fn public_contract(&self) -> Crate {
+        let mut out = self.clone();
+        for items in [
+            &mut out.traits,
+            &mut out.structs,
+            &mut out.enums,
+            &mut out.impls,
+            &mut out.functions,
+            &mut out.macros,
+            &mut out.statics,
+            &mut out.constants,
+            &mut out.unions,
+            &mut out.assoc_items,
+            &mut out.type_aliases,
+        ] {
+            items.retain(|item| {
+                item.stability.is_stable() && !item.is_hidden && !item.is_deprecated
+            });
+        }
+        out
+    }
+
+    /// Remove the impls generated by `#[derive(..)]`, keeping only the impls
+    /// which were written by hand.
+    pub fn retain_handwritten_impls(&mut self) {
+        self.impls.retain(|item| !item.derived);
+    }
+
+    /// All items without doc comments, leaving out impls since those are
+    /// rarely documented.
+    pub fn undocumented(&self) -> Vec<&item::Item> {
+        self.iter_items()
+            .filter(|item| item.kind != "impl" && !item.is_documented)
+            .collect()
+    }
+
+    /// All unstable items in the crate, regardless of kind.
+    pub fn unstable_items(&self) -> Vec<&item::Item> {
+        self.iter_items()
+            .filter(|item| item.stability.is_unstable())
+            .collect()
+    }
+
+    /// Rewrite the path of every item, e.g. to present `core::` items as if
+    /// they lived in `std::`.
+    pub fn rename_paths(&mut self, mut rename: impl FnMut(&str) -> String) {
+        for items in [
+            &mut self.traits,
+            &mut self.structs,
+            &mut self.enums,
+            &mut self.impls,
+            &mut self.functions,
+            &mut self.assoc_items,
+            &mut self.type_aliases,
+            &mut self.unions,
+            &mut self.constants,
+            &mut self.statics,
+            &mut self.macros,
+        ] {
+            for item in items.iter_mut() {
+                item.path = rename(&item.path);
+            }
+        }
+    }
+
+    /// Check the crate for signs that the output may be incomplete.
+    ///
+    /// This includes the warnings recorded during parsing, as well as items
+    /// with empty signatures and impls of traits we couldn't resolve.
+    pub fn validate(&self) -> Vec<Warning> {
+        let mut out = self.warnings.clone();
+        for item in self.iter_items() {
+            if item.decl.is_empty() {
+                out.push(Warning::EmptyDecl {
+                    kind: item.kind,
+                    path: format!("{}::{}", item.path, item.name),
+                });
+            }
+        }
+        for impl_ in &self.impls {
+            if impl_.target_trait.starts_with("UNKNOWN: ") {
+                out.push(Warning::UnresolvedTrait {
+                    name: impl_.name.clone(),
+                    decl: impl_.decl.clone(),
+                });
+            }
+        }
+        out
+    }
+
+    /// Every collection of items, named after its field.
+    pub(crate) fn collections(&self) -> [(&'static str, &Vec<item::Item>); 11] {
+        [
+            ("traits", &self.traits),
+            ("structs", &self.structs),
+            ("enums", &self.enums),
+            ("unions", &self.unions),
+            ("impls", &self.impls),
+            ("functions", &self.functions),
+            ("type_aliases", &self.type_aliases),
+            ("assoc_items", &self.assoc_items),
+            ("constants", &self.constants),
+            ("statics", &self.statics),
+            ("macros", &self.macros),
+        ]
+    }
+
+    /// Iterate over all items in the crate, regardless of kind
+    pub fn iter_items(&self) -> impl Iterator<Item = &item::Item> {
+        self.traits
+            .iter()
+            .chain(self.structs.iter())
+            .chain(self.enums.iter())
+            .chain(self.impls.iter())
+            .chain(self.functions.iter())
+            .chain(self.macros.iter())
+            .chain(self.statics.iter())
+            .chain(self.constants.iter())
+            .chain(self.unions.iter())
+            .chain(self.assoc_items.iter())
+            .chain(self.type_aliases.iter())
+    }
+
+    /// How many traits are in this crate, leaving out auto traits like `Send`
+    /// and `Sync`.
+    pub fn trait_count_excluding_auto(&self) -> usize {
+        self.traits.iter().filter(|t| !t.is_auto).count()
+    }
+
+    /// The structs, enums and unions without any inherent methods, which are often
+    /// plain data types.
+    pub fn methodless_types(&self) -> Vec<&item::Item> {
+        self.structs
+            .iter()
+            .chain(&self.enums)
+            .chain(&self.unions)
+            .filter(|item| item.fn_count == 0)
+            .collect()
+    }
+
+    /// How many traits have a given number of methods, keyed by method count.
+    pub fn trait_method_count_distribution(&self) -> BTreeMap<usize, usize> {
+        let mut distribution = BTreeMap::new();
+        for item in &self.traits {
+            *distribution.entry(item.fn_count).or_default() += 1;
+        }
+        distribution
+    }
+
+    /// The distinct module paths which contain items.
+    pub fn paths(&self) -> BTreeSet<String> {
+        // Methods are pathed under their trait or type rather than a module
+        let parents: BTreeSet<_> = self
+            .traits
+            .iter()
+            .chain(&self.structs)
+            .chain(&self.enums)
+            .chain(&self.unions)
+            .map(|item| format!("{}::{}", item.path, item.name))
+            .collect();
+        self.iter_items()
+            .filter(|item| !parents.contains(&item.path))
+            .map(|item| item.path.clone())
+            .collect()
+    }
+
+    /// Group all items by the shape of their signature, so items like every
+    /// `fn() -> Self` can be found together. See `Item::signature`.
+    pub fn signature_index(&self) -> BTreeMap<String, Vec<&item::Item>> {
+        let mut index: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for item in self.iter_items() {
+            index.entry(item.signature()).or_default().push(item);
+        }
+        index
+    }
+
+    /// A combined hash of every item's `content_hash`, for cheaply detecting
+    /// whether the API of a crate changed.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = item::Fnv::new();
+        for item in self.iter_items() {
+            hasher.write(&item.content_hash().to_le_bytes());
+        }
+        hasher.finish()
+    }
+
+    /// All items grouped by module, and within each module ordered by kind in
+    /// the order rustdoc lists them, then by name.
+    pub fn sort_within_module(&self) -> Vec<&item::Item> {
+        const KINDS: [&str; 12] = [
+            "macro",
+            "proc_attribute",
+            "proc_derive",
+            "struct",
+            "enum",
+            "union",
+            "trait",
+            "function",
+            "type",
+            "const",
+            "static",
+            "impl",
+        ];
+        let rank = |kind: &str| KINDS.iter().position(|k| *k == kind);
+        let mut items: Vec<_> = self.iter_items().collect();
+        items.sort_by(|a, b| {
+            (&a.path, rank(a.kind), &a.name, &a.id).cmp(&(&b.path, rank(b.kind), &b.name, &b.id))
+        });
+        items
+    }
+
+    /// Convert the crate into a single list of items of any kind
+    pub fn into_items(self) -> Vec<AnyItem> {
+        let mut out = vec![];
+        out.extend(self.traits.into_iter().map(AnyItem::Trait));
+        out.extend(self.structs.into_iter().map(AnyItem::Struct));
+        out.extend(self.enums.into_iter().map(AnyItem::Enum));
+        out.extend(self.impls.into_iter().map(AnyItem::Impl));
+        out.extend(self.functions.into_iter().map(AnyItem::Function));
+        out.extend(self.macros.into_iter().map(AnyItem::Macro));
+        out.extend(self.statics.into_iter().map(AnyItem::Static));
+        out.extend(self.constants.into_iter().map(AnyItem::Constant));
+        out.extend(self.unions.into_iter().map(AnyItem::Union));
+        out.extend(self.assoc_items.into_iter().map(AnyItem::AssocItem));
+        out.extend(self.type_aliases.into_iter().map(AnyItem::TypeAlias));
+        out
+    }
+
+    /// Count the items of each kind by stability
+    pub fn stats(&self) -> CrateStats {
+        let all: Stats = self
+            .iter_items()
+            .map(|item| (item.stability, item.has_generics))
+            .collect();
+        CrateStats {
+            traits: Stats::from_items(&self.traits),
+            functions: Stats::from_items(&self.functions),
+            structs: Stats::from_items(&self.structs),
+            enums: Stats::from_items(&self.enums),
+            unions: Stats::from_items(&self.unions),
+            impls: Stats::from_items(&self.impls),
+            type_aliases: Stats::from_items(&self.type_aliases),
+            constants: Stats::from_items(&self.constants),
+            statics: Stats::from_items(&self.statics),
+            macros: Stats::from_items(&self.macros),
+            assoc_items: Stats::from_items(&self.assoc_items),
+            stable_fraction: all.stable_fraction,
+        }
+    }
+
+    /// Write the items of the crate as CSV, one row per item, grouped by kind.
+    pub fn write_csv<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        for items in [
+            &self.structs,
+            &self.enums,
+            &self.unions,
+            &self.type_aliases,
+            &self.constants,
+            &self.statics,
+            &self.macros,
+            &self.traits,
+            &self.functions,
+            &self.assoc_items,
+            &self.impls,
+        ] {
+            for item in items {
+                writer.serialize(item)?;
+            }
+        }
+        writer.flush()
+    }
+
+    /// Write the items of the crate into a SQLite database, with a table per
+    /// kind of item and a column per field.
+    pub fn write_sqlite(&self, conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+        sqlite::write(self, conn)
+    }
+
+    /// All items of the crate as JSON, tagged with the `SCHEMA_VERSION`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let items: Vec<_> = self.iter_items().collect();
+        serde_json::json!({
+            "schema_version": SCHEMA_VERSION,
+            "items": items,
+        })
+    }
+
+    /// Headline metrics of the crate as a compact JSON object, for dashboards
+    /// which don't need the items themselves.
+    pub fn summary_json(&self) -> serde_json::Value {
+        let stats = self.stats();
+        let total = self.iter_items().count();
+        let stable = self.counts_by_stability().0;
+        let generics = self.iter_items().filter(|item| item.has_generics).count();
+        let counts: serde_json::Map<_, _> = self
+            .collections()
+            .iter()
+            .map(|(kind, items)| (kind.to_string(), items.len().into()))
+            .collect();
+        let fraction = |count: usize, of: usize| match of {
+            0 => 0.0,
+            of => count as f64 / of as f64,
+        };
+        serde_json::json!({
+            "schema_version": SCHEMA_VERSION,
+            "total_items": total,
+            "counts": counts,
+            "stable_fraction": fraction(stable, total),
+            "generic_fraction": fraction(generics, total),
+            "traits_per_adt": fraction(stats.impls.stable, stats.adts().stable),
+        })
+    }
+
+    /// Count all items, including impls, as `(stable, unstable)`. Deprecated
+    /// items count as stable.
+    pub fn counts_by_stability(&self) -> (usize, usize) {
+        let stable = self
+            .iter_items()
+            .filter(|item| item.stability.is_stable())
+            .count();
+        let unstable = self
+            .iter_items()
+            .filter(|item| item.stability.is_unstable())
+            .count();
+        (stable, unstable)
+    }
+
+    /// Count all items by their visibility
+    pub fn visibility_stats(&self) -> VisibilityStats {
+        self.iter_items().map(|item| item.visibility).collect()
     }
 
     /// Output the contents of the crate as a table
@@ -108,29 +575,49 @@ impl Crate {
         table::to_table(self)
     }
 
+    /// Output the contents of the crate as a table, with the methods of each
+    /// trait, struct, and enum listed in indented rows below it
+    pub fn to_expanded_table(&self) -> TableStruct {
+        table::to_expanded_table(self)
+    }
+
+    /// Get the name of an item, recording a warning if it doesn't have one.
+    fn item_name(&mut self, item: &rustdoc_types::Item) -> Option<String> {
+        if item.name.is_none() {
+            self.warnings.push(Warning::Unnamed {
+                id: item.id.0.clone(),
+            });
+        }
+        item.name.clone()
+    }
+
     fn parse_traits(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
         for (item, trait_) in db.find_traits(items) {
-            let trait_name = item.name.unwrap();
-            let decl = format_trait(&trait_name, &trait_);
+            let trait_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_trait(db, &trait_name, &trait_);
             let has_generics = contains_generics(&trait_.generics);
 
             let fn_path = format!("{path_name}::{}", &trait_name);
             let fn_count = self.count_functions(db, &trait_.items, &fn_path, has_generics);
-
-            let stability = parse_stability(&item.attrs);
+            let provided_fn_count = db
+                .find_functions(&trait_.items)
+                .into_iter()
+                .filter(|(_, fn_)| fn_.has_body)
+                .count();
+            let assoc_count = db.find_assoc_items(&trait_.items).len();
 
             self.traits.push(item::Item {
-                kind: "trait",
-                id: item.id.0,
-                name: trait_name.clone(),
                 has_generics,
-                is_const: false,
-                target_trait: String::new(),
-                is_async: false,
-                path: path_name.to_string(),
-                stability,
+                is_auto: trait_.is_auto,
+                is_unsafe: trait_.is_unsafe,
                 fn_count,
+                provided_fn_count,
+                assoc_count,
                 decl,
+                ..new_item("trait", trait_name.clone(), path_name, &item)
             });
         }
     }
@@ -138,59 +625,156 @@ impl Crate {
     fn parse_structs(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
         // dbg!(items.contains(&Id(String::from("0:3663:9709"))));
         for (item, strukt) in db.find_structs(items) {
-            let strukt_name = item.name.unwrap();
+            let strukt_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
             // println!("{strukt_name}");
-            let decl = format_struct(&strukt_name, &strukt);
+            let decl = format_struct(db, &strukt_name, &strukt);
             let has_generics = contains_generics(&strukt.generics);
 
             let strukt_path = format!("{path_name}::{}", &strukt_name);
             let fn_count = self.count_inherent_impls(db, &strukt.impls, &strukt_path);
 
-            let stability = parse_stability(&item.attrs);
+            let stability = parse_stability(&item);
             self.parse_trait_impls(db, &strukt.impls, path_name, stability);
 
             self.structs.push(item::Item {
-                kind: "struct",
-                id: item.id.0,
-                name: strukt_name.clone(),
-                is_const: false,
-                is_async: false,
                 has_generics,
-                path: path_name.to_string(),
-                target_trait: String::new(),
-                stability: parse_stability(&item.attrs),
                 fn_count,
+                field_count: struct_field_count(&strukt.kind),
+                decl,
+                ..new_item("struct", strukt_name.clone(), path_name, &item)
+            });
+        }
+    }
+
+    fn parse_unions(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for (item, union) in db.find_unions(items) {
+            let union_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_union(db, &union_name, &union);
+            let has_generics = contains_generics(&union.generics);
+
+            let union_path = format!("{path_name}::{}", &union_name);
+            let fn_count = self.count_inherent_impls(db, &union.impls, &union_path);
+
+            let stability = parse_stability(&item);
+            self.parse_trait_impls(db, &union.impls, path_name, stability);
+
+            self.unions.push(item::Item {
+                has_generics,
+                fn_count,
+                field_count: union.fields.len(),
                 decl,
+                ..new_item("union", union_name.clone(), path_name, &item)
             });
         }
     }
 
     fn parse_enums(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
         for (item, enum_) in db.find_enums(items) {
-            let trait_name = item.name.unwrap();
-            let decl = format_enum(&trait_name, &enum_);
+            let trait_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_enum(db, &trait_name, &enum_);
 
             let enum_path = format!("{path_name}::{}", &trait_name);
             let fn_count = self.count_inherent_impls(db, &enum_.impls, &enum_path);
-            let stability = parse_stability(&item.attrs);
+            let stability = parse_stability(&item);
             self.parse_trait_impls(db, &enum_.impls, path_name, stability);
 
             self.enums.push(item::Item {
-                kind: "enum",
-                id: item.id.0,
-                name: trait_name.clone(),
                 has_generics: contains_generics(&enum_.generics),
-                is_const: false,
-                is_async: false,
-                path: path_name.to_string(),
-                target_trait: String::new(),
-                stability,
                 fn_count,
+                variant_count: enum_.variants.len(),
+                decl,
+                ..new_item("enum", trait_name.clone(), path_name, &item)
+            });
+        }
+    }
+
+    fn parse_type_aliases(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for (item, alias) in db.find_type_aliases(items) {
+            let alias_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_type_alias(db, &alias_name, &alias);
+
+            self.type_aliases.push(item::Item {
+                has_generics: contains_generics(&alias.generics),
+                max_generic_depth: generic_depth(&alias.type_),
+                decl,
+                ..new_item("type", alias_name, path_name, &item)
+            });
+        }
+    }
+
+    fn parse_macros(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for item in db.find_macros(items) {
+            let macro_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let (kind, decl) = match &item.inner {
+                ItemEnum::Macro(source) => ("macro", source.clone()),
+                ItemEnum::ProcMacro(proc_macro) => format_proc_macro(&macro_name, proc_macro),
+                _ => continue,
+            };
+
+            self.macros.push(item::Item {
+                decl,
+                ..new_item(kind, macro_name, path_name, &item)
+            });
+        }
+    }
+
+    fn parse_constants(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for (item, constant) in db.find_constants(items) {
+            let name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_free_constant(db, &name, &constant);
+
+            self.constants.push(item::Item {
+                is_const: true,
+                max_generic_depth: generic_depth(&constant.type_),
                 decl,
+                ..new_item("const", name, path_name, &item)
             });
         }
     }
 
+    fn parse_statics(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for (item, static_) in db.find_statics(items) {
+            let name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let decl = format_static(db, &name, &static_);
+
+            self.statics.push(item::Item {
+                max_generic_depth: generic_depth(&static_.type_),
+                decl,
+                ..new_item("static", name, path_name, &item)
+            });
+        }
+    }
+
+    /// Primitives aren't collected as items themselves, but we do want the
+    /// trait impls on them, e.g. `impl Default for (u8, u16)`.
+    fn parse_primitives(&mut self, db: &Database, items: &[rustdoc_types::Id], path_name: &str) {
+        for (item, primitive) in db.find_primitives(items) {
+            let stability = parse_stability(&item);
+            self.parse_trait_impls(db, &primitive.impls, path_name, stability);
+        }
+    }
+
     fn parse_trait_impls(
         &mut self,
         db: &Database,
@@ -201,41 +785,62 @@ impl Crate {
         for (item, impl_) in db.find_impls(items) {
             let has_generics = contains_generics(&impl_.generics);
 
+            // Generated impls aren't written in the crate, so they're opt-in
+            let is_generated = impl_.synthetic || impl_.blanket_impl.is_some();
+            if is_generated && !db.options().show_auto {
+                continue;
+            }
+
             // We're only interested in trait impls
             if let Some(trait_) = impl_.trait_.clone() {
                 db.find_enums(&impl_.items)
                     .into_iter()
                     .for_each(|(item, _)| {
-                        if let Stability::Unstable = parse_stability(&item.attrs) {
+                        if let Stability::Unstable = parse_stability(&item) {
                             stability = Stability::Unstable;
                         }
                     });
-                match db.find_traits(&[trait_.id.clone()]).into_iter().next() {
-                    Some((trait_item, _)) => {
-                        if let Stability::Unstable = parse_stability(&trait_item.attrs) {
-                            stability = Stability::Unstable;
-                        }
+                // Assume stable stability if it's an external trait
+                let trait_id = std::slice::from_ref(&trait_.id);
+                if let Some((trait_item, _)) = db.find_traits(trait_id).into_iter().next() {
+                    if let Stability::Unstable = parse_stability(&trait_item) {
+                        stability = Stability::Unstable;
                     }
-                    // Assume stable stability if it's an external trait
-                    None => {}
                 }
 
                 let name = trait_.name.clone();
                 let target_path = trait_.id.0;
 
-                let decl = format_impl(impl_);
+                let for_type = format_type(db, &impl_.for_);
+                let for_type_id = self_type_id(&impl_.for_).map(|id| id.0.clone());
+                let is_unsafe = impl_.is_unsafe;
+                let max_generic_depth = match &trait_.args {
+                    Some(args) => generic_depth(&impl_.for_).max(generic_args_depth(args)),
+                    None => generic_depth(&impl_.for_),
+                };
+                let assoc_consts = format_impl_consts(db, &impl_.items);
+                let assoc_const_count = assoc_consts.len();
+                let lifetime_only_generics = has_only_lifetime_generics(&impl_.generics);
+                let decl = format_impl(db, impl_, &assoc_consts);
+                // Impls inherit the stability of their type, but are only
+                // deprecated if they're marked so themselves.
+                let stability = match stability {
+                    Stability::Deprecated => with_deprecation(Stability::Stable, &item),
+                    stability => with_deprecation(stability, &item),
+                };
                 self.impls.push(item::Item {
-                    kind: "impl",
-                    id: item.id.0,
-                    name,
                     has_generics,
-                    is_const: false,
-                    is_async: false,
-                    path: path_name.to_string(),
+                    is_unsafe,
                     target_trait: target_path,
+                    for_type,
+                    for_type_id,
                     stability,
-                    fn_count: 0,
+                    assoc_const_count,
+                    lifetime_only_generics,
+                    max_generic_depth,
                     decl,
+                    derived: is_derived(&item.attrs),
+                    ..new_item("impl", name, path_name, &item)
                 });
             }
         }
@@ -254,11 +859,54 @@ impl Crate {
                 continue;
             }
             let has_generics = contains_generics(&impl_.generics);
-            count += self.count_functions(db, &impl_.items, &path_name, has_generics);
+            count += self.count_functions(db, &impl_.items, path_name, has_generics);
+            self.parse_assoc_items(db, &impl_.items, path_name, has_generics);
         }
         count
     }
 
+    fn parse_assoc_items(
+        &mut self,
+        db: &Database,
+        items: &[rustdoc_types::Id],
+        path_name: &str,
+        parent_has_generics: bool,
+    ) {
+        for item in db.find_assoc_items(items) {
+            let assoc_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
+            let (kind, has_generics, max_generic_depth, decl) = match &item.inner {
+                ItemEnum::AssocConst { type_, default } => (
+                    "const",
+                    false,
+                    generic_depth(type_),
+                    format_assoc_const(db, &assoc_name, type_, default.as_deref()),
+                ),
+                ItemEnum::AssocType {
+                    generics,
+                    bounds,
+                    default,
+                } => (
+                    "type",
+                    contains_generics(generics),
+                    default.as_ref().map_or(0, generic_depth),
+                    format_assoc_type(db, &assoc_name, generics, bounds, default.as_ref()),
+                ),
+                _ => continue,
+            };
+
+            self.assoc_items.push(item::Item {
+                has_generics: has_generics || parent_has_generics,
+                is_const: kind == "const",
+                max_generic_depth,
+                decl,
+                ..new_item(kind, assoc_name, path_name, &item)
+            });
+        }
+    }
+
     fn count_functions(
         &mut self,
         db: &Database,
@@ -267,27 +915,119 @@ impl Crate {
         parent_has_generics: bool,
     ) -> usize {
         let mut count = 0;
-        for (item, fn_) in db.find_functions(&items) {
+        for (item, fn_) in db.find_functions(items) {
+            let function_name = match self.item_name(&item) {
+                Some(name) => name,
+                None => continue,
+            };
             count += 1;
-            let function_name = item.name.unwrap();
             self.functions.push(item::Item {
-                kind: "function",
-                id: item.id.0,
-                name: function_name.clone(),
                 has_generics: contains_generics(&fn_.generics) || parent_has_generics,
                 is_const: fn_.header.const_,
                 is_async: fn_.header.async_,
-                path: path_name.to_owned(),
-                target_trait: String::new(),
-                stability: parse_stability(&item.attrs),
-                decl: format_function(&function_name, &fn_),
-                fn_count: 0,
+                is_unsafe: fn_.header.unsafe_,
+                decl: format_function(db, &function_name, &fn_),
+                max_generic_depth: function_generic_depth(&fn_.decl),
+                return_type: fn_.decl.output.as_ref().map(|ty| outer_type_name(db, ty)),
+                never_returns: fn_.decl.output.as_ref().is_some_and(is_never),
+                ..new_item("function", function_name.clone(), path_name, &item)
             });
         }
         count
     }
 }
 
+/// An item of the given kind with the fields every kind shares filled in from
+/// its rustdoc item, and everything else left empty for the parser to set.
+fn new_item(kind: item::Kind, name: String, path: &str, item: &rustdoc_types::Item) -> item::Item {
+    item::Item {
+        kind,
+        id: item.id.0.clone(),
+        name,
+        path: path.to_string(),
+        target_trait: String::new(),
+        for_type: String::new(),
+        for_type_id: None,
+        decl: String::new(),
+        has_generics: false,
+        is_const: false,
+        is_async: false,
+        is_auto: false,
+        is_unsafe: false,
+        stability: parse_stability(item),
+        feature: stability_value(&item.attrs, "feature"),
+        issue: tracking_issue(&item.attrs),
+        since: stability_value(&item.attrs, "since"),
+        fn_count: 0,
+        provided_fn_count: 0,
+        assoc_count: 0,
+        assoc_const_count: 0,
+        field_count: 0,
+        variant_count: 0,
+        lifetime_only_generics: false,
+        max_generic_depth: 0,
+        return_type: None,
+        never_returns: false,
+        is_hidden: is_hidden(&item.attrs),
+        is_documented: is_documented(item.docs.as_deref()),
+        doc_summary: doc_summary(item.docs.as_deref()),
+        docs: doc_paragraph(item.docs.as_deref()),
+        derived: false,
+        is_deprecated: item.deprecation.is_some(),
+        visibility: Visibility::from(&item.visibility),
+    }
+}
+
+/// Merge two sorted lists of items into `left`, skipping items which are
+/// identical to the last item inserted.
+fn merge_dedup(left: &mut Vec<item::Item>, right: &mut Vec<item::Item>) {
+    let mut lhs = std::mem::take(left).into_iter().peekable();
+    let mut rhs = std::mem::take(right).into_iter().peekable();
+    left.reserve(lhs.len() + rhs.len());
+    loop {
+        let next = match (lhs.peek(), rhs.peek()) {
+            (Some(l), Some(r)) if l <= r => lhs.next(),
+            (Some(_), Some(_)) => rhs.next(),
+            (Some(_), None) => lhs.next(),
+            (None, Some(_)) => rhs.next(),
+            (None, None) => break,
+        };
+        let next = next.unwrap();
+        if left.last() != Some(&next) {
+            left.push(next);
+        }
+    }
+}
+
+/// The ID of the type an impl is for, looking through references so that
+/// `impl Trait for &Foo` resolves to `Foo`.
+fn self_type_id(ty: &Type) -> Option<&rustdoc_types::Id> {
+    match ty {
+        Type::ResolvedPath(path) => Some(&path.id),
+        Type::BorrowedRef { type_, .. } => self_type_id(type_),
+        _ => None,
+    }
+}
+
+/// How many fields a struct has. Private fields of tuple structs still take
+/// up a position, so they're counted too.
+fn struct_field_count(kind: &rustdoc_types::StructKind) -> usize {
+    match kind {
+        rustdoc_types::StructKind::Unit => 0,
+        rustdoc_types::StructKind::Tuple(fields) => fields.len(),
+        rustdoc_types::StructKind::Plain { fields, .. } => fields.len(),
+    }
+}
+
+/// Generic over lifetimes, but nothing else, e.g. `impl<'a> Trait for Foo<'a>`.
+fn has_only_lifetime_generics(generics: &rustdoc_types::Generics) -> bool {
+    let has_lifetimes = generics
+        .params
+        .iter()
+        .any(|p| matches!(p.kind, GenericParamDefKind::Lifetime { .. }));
+    has_lifetimes && !contains_generics(generics)
+}
+
 fn contains_generics(generics: &rustdoc_types::Generics) -> bool {
     let params = &generics
         .params
@@ -303,76 +1043,443 @@ fn contains_generics(generics: &rustdoc_types::Generics) -> bool {
     (params + wheres) != 0
 }
 
-fn format_function(name: &str, fn_: &rustdoc_types::Function) -> String {
+fn format_function(db: &Database, name: &str, fn_: &rustdoc_types::Function) -> String {
     if name == "merge_sort" {
-        return format!("<merge sort is unstable and annoyingly complicated>");
+        return "<merge sort is unstable and annoyingly complicated>".to_string();
     }
+    let name = format_ident(name);
+    // Qualifiers are rendered in the order Rust requires: `const async unsafe`
     let is_const = if fn_.header.const_ { "const " } else { "" };
-    let is_unsafe = if fn_.header.unsafe_ { "unsafe " } else { "" };
     let is_async = if fn_.header.async_ { "async " } else { "" };
+    let is_unsafe = if fn_.header.unsafe_ { "unsafe " } else { "" };
+    let abi = format_abi(&fn_.header.abi);
     let body = if fn_.has_body { " { .. }" } else { ";" };
     let output = match &fn_.decl.output {
-        Some(ty) => format!(" -> {}", format_type(&ty)),
+        Some(ty) => format!(" -> {}", format_type(db, ty)),
         None => String::new(),
     };
     let args = &fn_
         .decl
         .inputs
         .iter()
-        .map(|(name, ty)| format!("{name}: {}", format_type(ty)))
+        .map(|(name, ty)| format_arg(db, name, ty))
         .collect::<Vec<_>>();
     let args = args.join(", ");
-    let params = format_generic_params(&fn_.generics.params);
-    let where_bounds = format_where_bounds(&fn_.generics.where_predicates);
-    format!("{is_const}{is_unsafe}{is_async}fn {name}{params}({args}){output}{where_bounds}{body}")
+    let params = format_generic_params(db, &fn_.generics.params);
+    let where_bounds = format_where_bounds(db, &fn_.generics.where_predicates);
+    format!(
+        "{is_const}{is_async}{is_unsafe}{abi}fn {name}{params}({args}){output}{where_bounds}{body}"
+    )
+}
+
+/// Format a function argument, using the shorthand forms for receivers.
+fn format_arg(db: &Database, name: &str, ty: &Type) -> String {
+    let self_ty = Type::Generic("Self".to_string());
+    match ty {
+        ty if name == "self" && *ty == self_ty => "self".to_string(),
+        Type::BorrowedRef {
+            lifetime,
+            mutable,
+            type_,
+        } if name == "self" && **type_ == self_ty => {
+            let lifetime = format_ref_lifetime(db, lifetime.as_deref());
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{lifetime}{mutable}self")
+        }
+        ty => format!("{}: {}", format_ident(name), format_type(db, ty)),
+    }
+}
+
+/// Escape identifiers which are keywords as raw identifiers, e.g. a parameter
+/// named `type` renders as `r#type`.
+pub(crate) fn format_ident(name: &str) -> String {
+    // `self`, `Self`, `super`, and `crate` can't be raw identifiers.
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// The lifetime of a reference, including its trailing space.
+fn format_ref_lifetime(db: &Database, lifetime: Option<&str>) -> String {
+    match lifetime {
+        Some(lt) if !db.options().elide_lifetimes => format!("{lt} "),
+        _ => String::new(),
+    }
 }
 
-fn format_trait(name: &str, trait_: &rustdoc_types::Trait) -> String {
+/// The deepest nesting of generic args in a function's inputs and output.
+fn function_generic_depth(decl: &rustdoc_types::FnDecl) -> usize {
+    let inputs = decl.inputs.iter().map(|(_, ty)| ty);
+    inputs
+        .chain(&decl.output)
+        .map(generic_depth)
+        .max()
+        .unwrap_or(0)
+}
+
+/// How deeply generic args are nested in a type, e.g. 3 for
+/// `Vec<Option<Box<T>>>`. References, slices and the like don't add a level.
+fn generic_depth(ty: &Type) -> usize {
+    match ty {
+        Type::ResolvedPath(path) => path.args.as_deref().map_or(0, generic_args_depth),
+        Type::DynTrait(dyn_trait) => dyn_trait
+            .traits
+            .iter()
+            .filter_map(|poly| poly.trait_.args.as_deref())
+            .map(generic_args_depth)
+            .max()
+            .unwrap_or(0),
+        Type::Tuple(types) => types.iter().map(generic_depth).max().unwrap_or(0),
+        Type::Slice(type_)
+        | Type::Array { type_, .. }
+        | Type::RawPointer { type_, .. }
+        | Type::BorrowedRef { type_, .. } => generic_depth(type_),
+        Type::QualifiedPath {
+            args, self_type, ..
+        } => generic_depth(self_type).max(generic_args_depth(args)),
+        _ => 0,
+    }
+}
+
+fn generic_args_depth(args: &GenericArgs) -> usize {
+    let depth = match args {
+        GenericArgs::AngleBracketed { args, bindings } => {
+            let args = args.iter().map(|arg| match arg {
+                GenericArg::Type(ty) => generic_depth(ty),
+                _ => 0,
+            });
+            let bindings = bindings.iter().map(|binding| match &binding.binding {
+                TypeBindingKind::Equality(Term::Type(ty)) => generic_depth(ty),
+                _ => 0,
+            });
+            match args.len() + bindings.len() {
+                0 => return 0,
+                _ => args.chain(bindings).max().unwrap_or(0),
+            }
+        }
+        GenericArgs::Parenthesized { inputs, output } => inputs
+            .iter()
+            .chain(output)
+            .map(generic_depth)
+            .max()
+            .unwrap_or(0),
+    };
+    depth + 1
+}
+
+/// The name of the outermost type, without any generic args or module path.
+fn outer_type_name(db: &Database, ty: &Type) -> String {
+    match ty {
+        Type::ResolvedPath(path) => match path.name.rsplit_once("::") {
+            Some((_, name)) => name.to_string(),
+            None => path.name.clone(),
+        },
+        ty => format_type(db, ty),
+    }
+}
+
+fn format_abi(abi: &Abi) -> String {
+    let (name, unwind) = match abi {
+        Abi::Rust => return String::new(),
+        // rustdoc already includes the quotes for ABIs it doesn't know about
+        Abi::Other(name) => return format!("extern {name} "),
+        Abi::C { unwind } => ("C", unwind),
+        Abi::Cdecl { unwind } => ("cdecl", unwind),
+        Abi::Stdcall { unwind } => ("stdcall", unwind),
+        Abi::Fastcall { unwind } => ("fastcall", unwind),
+        Abi::Aapcs { unwind } => ("aapcs", unwind),
+        Abi::Win64 { unwind } => ("win64", unwind),
+        Abi::SysV64 { unwind } => ("sysv64", unwind),
+        Abi::System { unwind } => ("system", unwind),
+    };
+    let unwind = if *unwind { "-unwind" } else { "" };
+    format!("extern \"{name}{unwind}\" ")
+}
+
+fn format_trait(db: &Database, name: &str, trait_: &rustdoc_types::Trait) -> String {
+    let name = format_ident(name);
     let is_auto = if trait_.is_auto { "auto " } else { "" };
     let is_unsafe = if trait_.is_unsafe { "unsafe " } else { "" };
-    let params = format_generic_params(&trait_.generics.params);
-    let where_bounds = format_where_bounds(&trait_.generics.where_predicates);
-    let trait_bounds = format_generic_bounds(&trait_.bounds);
-    format!("{is_unsafe}{is_auto}trait {name}{params}{trait_bounds} {where_bounds}{{ }}")
+    let params = format_generic_params(db, &trait_.generics.params);
+    let where_bounds = format_where_bounds(db, &trait_.generics.where_predicates);
+    let trait_bounds = format_generic_bounds(db, &trait_.bounds);
+    // Methods are their own items, but associated consts and types are only
+    // visible through the trait's declaration.
+    let assoc_items = format_trait_assoc_items(db, &trait_.items);
+    let body = match assoc_items.len() {
+        0 => "{ }".to_string(),
+        _ => format!("{{ {} }}", assoc_items.join(" ")),
+    };
+    format!("{is_unsafe}{is_auto}trait {name}{params}{trait_bounds}{where_bounds} {body}")
 }
 
-fn format_struct(name: &str, strukt: &rustdoc_types::Struct) -> String {
-    let params = format_generic_params(&strukt.generics.params);
-    let where_bounds = format_where_bounds(&strukt.generics.where_predicates);
-    format!("struct {name}{params} {where_bounds} {{ .. }}")
+/// The associated consts and types declared by a trait, e.g. `type Item;`.
+fn format_trait_assoc_items(db: &Database, items: &[rustdoc_types::Id]) -> Vec<String> {
+    db.find_assoc_items(items)
+        .into_iter()
+        .filter_map(|item| {
+            let name = item.name.as_deref()?;
+            match &item.inner {
+                ItemEnum::AssocConst { type_, default } => {
+                    Some(format_assoc_const(db, name, type_, default.as_deref()))
+                }
+                ItemEnum::AssocType {
+                    generics,
+                    bounds,
+                    default,
+                } => Some(format_assoc_type(
+                    db,
+                    name,
+                    generics,
+                    bounds,
+                    default.as_ref(),
+                )),
+                _ => None,
+            }
+        })
+        .collect()
 }
 
-fn format_enum(name: &str, strukt: &rustdoc_types::Enum) -> String {
-    let params = format_generic_params(&strukt.generics.params);
-    let where_bounds = format_where_bounds(&strukt.generics.where_predicates);
-    format!("enum {name}{params} {where_bounds} {{ .. }}")
+fn format_struct(db: &Database, name: &str, strukt: &rustdoc_types::Struct) -> String {
+    let name = format_ident(name);
+    let params = format_generic_params(db, &strukt.generics.params);
+    let where_bounds = format_where_bounds(db, &strukt.generics.where_predicates);
+    // Unit and tuple structs put their where clause after the fields.
+    match &strukt.kind {
+        rustdoc_types::StructKind::Unit => format!("struct {name}{params}{where_bounds};"),
+        rustdoc_types::StructKind::Tuple(fields) if fields.is_empty() => {
+            format!("struct {name}{params}(){where_bounds};")
+        }
+        rustdoc_types::StructKind::Tuple(fields) if db.options().fields => {
+            let fields = format_tuple_fields(db, fields);
+            format!("struct {name}{params}{fields}{where_bounds};")
+        }
+        rustdoc_types::StructKind::Tuple(_) => format!("struct {name}{params}(..){where_bounds};"),
+        rustdoc_types::StructKind::Plain {
+            fields,
+            fields_stripped,
+        } if db.options().fields => {
+            let fields = format_named_fields(db, fields, *fields_stripped);
+            format!("struct {name}{params}{where_bounds} {fields}")
+        }
+        rustdoc_types::StructKind::Plain { .. } => {
+            format!("struct {name}{params}{where_bounds} {{ .. }}")
+        }
+    }
+}
+
+/// Render the types of tuple fields, e.g. `(u8, _)`. Private and hidden
+/// fields still take up a position, so they're rendered as `_`.
+fn format_tuple_fields(db: &Database, ids: &[Option<rustdoc_types::Id>]) -> String {
+    let fields: Vec<_> = ids
+        .iter()
+        .map(|id| {
+            let field = id
+                .as_ref()
+                .and_then(|id| db.find_fields(std::slice::from_ref(id)).pop());
+            match field {
+                Some((_, ty)) => format_type(db, &ty),
+                None => "_".to_string(),
+            }
+        })
+        .collect();
+    format!("({})", fields.join(", "))
+}
+
+/// Render named fields, e.g. `{ x: u8, .. }`, eliding any stripped fields.
+fn format_named_fields(db: &Database, ids: &[rustdoc_types::Id], fields_stripped: bool) -> String {
+    let mut fields: Vec<_> = db
+        .find_fields(ids)
+        .into_iter()
+        // A named field without a name would render as `: T`
+        .filter_map(|(item, ty)| {
+            let name = format_ident(item.name.as_deref()?);
+            Some(format!("{name}: {}", format_type(db, &ty)))
+        })
+        .collect();
+    if fields_stripped {
+        fields.push("..".to_string());
+    }
+    match fields.len() {
+        0 => "{}".to_string(),
+        _ => format!("{{ {} }}", fields.join(", ")),
+    }
+}
+
+fn format_union(db: &Database, name: &str, union: &rustdoc_types::Union) -> String {
+    let name = format_ident(name);
+    let params = format_generic_params(db, &union.generics.params);
+    let where_bounds = format_where_bounds(db, &union.generics.where_predicates);
+    if db.options().fields {
+        let fields = format_named_fields(db, &union.fields, union.fields_stripped);
+        return format!("union {name}{params}{where_bounds} {fields}");
+    }
+    format!("union {name}{params}{where_bounds} {{ .. }}")
+}
+
+fn format_enum(db: &Database, name: &str, enum_: &rustdoc_types::Enum) -> String {
+    let name = format_ident(name);
+    let params = format_generic_params(db, &enum_.generics.params);
+    let where_bounds = format_where_bounds(db, &enum_.generics.where_predicates);
+    if !db.options().variants && !db.options().fields {
+        return format!("enum {name}{params}{where_bounds} {{ .. }}");
+    }
+    let mut variants: Vec<_> = db
+        .find_variants(&enum_.variants)
+        .into_iter()
+        // Unnamed variants can't be rendered, so they're skipped
+        .filter_map(|(item, variant)| Some(format_variant(db, item.name.as_deref()?, &variant)))
+        .collect();
+    if enum_.variants_stripped {
+        variants.push("..".to_string());
+    }
+    match variants.len() {
+        0 => format!("enum {name}{params}{where_bounds} {{}}"),
+        _ => format!(
+            "enum {name}{params}{where_bounds} {{ {} }}",
+            variants.join(", ")
+        ),
+    }
 }
 
-fn format_generic_params(params: &[rustdoc_types::GenericParamDef]) -> String {
+fn format_variant(db: &Database, name: &str, variant: &rustdoc_types::Variant) -> String {
+    let name = format_ident(name);
+    let fields = match &variant.kind {
+        rustdoc_types::VariantKind::Plain => String::new(),
+        rustdoc_types::VariantKind::Tuple(fields) if db.options().fields => {
+            format_tuple_fields(db, fields)
+        }
+        rustdoc_types::VariantKind::Tuple(_) => "(..)".to_string(),
+        rustdoc_types::VariantKind::Struct {
+            fields,
+            fields_stripped,
+        } if db.options().fields => {
+            format!(" {}", format_named_fields(db, fields, *fields_stripped))
+        }
+        rustdoc_types::VariantKind::Struct { .. } => " { .. }".to_string(),
+    };
+    // Render the discriminant as it was written, e.g. `1 << 2` rather
+    // than the evaluated `4`.
+    let discriminant = match &variant.discriminant {
+        Some(discriminant) => format!(" = {}", discriminant.expr),
+        None => String::new(),
+    };
+    format!("{name}{fields}{discriminant}")
+}
+
+fn format_type_alias(db: &Database, name: &str, alias: &rustdoc_types::Typedef) -> String {
+    let name = format_ident(name);
+    let params = format_generic_params(db, &alias.generics.params);
+    let where_bounds = format_where_bounds(db, &alias.generics.where_predicates);
+    let ty = format_type(db, &alias.type_);
+    format!("type {name}{params}{where_bounds} = {ty};")
+}
+
+/// Proc macros are rendered the way they're invoked, e.g. `#[derive(Serialize)]`.
+fn format_proc_macro(name: &str, proc_macro: &rustdoc_types::ProcMacro) -> (&'static str, String) {
+    match proc_macro.kind {
+        rustdoc_types::MacroKind::Bang => ("macro", format!("{name}!(..)")),
+        rustdoc_types::MacroKind::Attr => ("proc_attribute", format!("#[{name}]")),
+        rustdoc_types::MacroKind::Derive => ("proc_derive", format!("#[derive({name})]")),
+    }
+}
+
+fn format_free_constant(db: &Database, name: &str, constant: &rustdoc_types::Constant) -> String {
+    let name = format_ident(name);
+    let ty = format_type(db, &constant.type_);
+    let value = format_constant(constant);
+    format!("const {name}: {ty} = {value};")
+}
+
+fn format_static(db: &Database, name: &str, static_: &rustdoc_types::Static) -> String {
+    let name = format_ident(name);
+    let mutable = if static_.mutable { "mut " } else { "" };
+    let ty = format_type(db, &static_.type_);
+    // rustdoc elides initializers it can't print
+    let value = match static_.expr.as_str() {
+        "_" => "{ .. }",
+        expr => expr,
+    };
+    format!("static {mutable}{name}: {ty} = {value};")
+}
+
+fn format_assoc_const(db: &Database, name: &str, ty: &Type, default: Option<&str>) -> String {
+    let name = format_ident(name);
+    let ty = format_type(db, ty);
+    match default {
+        Some(default) => format!("const {name}: {ty} = {default};"),
+        None => format!("const {name}: {ty};"),
+    }
+}
+
+fn format_assoc_type(
+    db: &Database,
+    name: &str,
+    generics: &rustdoc_types::Generics,
+    bounds: &[GenericBound],
+    default: Option<&Type>,
+) -> String {
+    let name = format_ident(name);
+    let params = format_generic_params(db, &generics.params);
+    let bounds = format_generic_bounds(db, bounds);
+    let where_bounds = format_where_bounds(db, &generics.where_predicates);
+    match default {
+        Some(ty) => format!(
+            "type {name}{params}{bounds}{where_bounds} = {};",
+            format_type(db, ty)
+        ),
+        None => format!("type {name}{params}{bounds}{where_bounds};"),
+    }
+}
+
+fn format_generic_params(db: &Database, params: &[rustdoc_types::GenericParamDef]) -> String {
+    let show_defaults = db.options().show_defaults;
     let mut out = vec![];
     for param in params {
         let name = &param.name;
         match &param.kind {
-            GenericParamDefKind::Lifetime { outlives: _ } => continue,
+            // Elided lifetimes aren't referenced anywhere, so their params
+            // would be unused.
+            GenericParamDefKind::Lifetime { .. } if db.options().elide_lifetimes => continue,
+            GenericParamDefKind::Lifetime { outlives } => match outlives.len() {
+                0 => out.push(name.clone()),
+                _ => out.push(format!("{name}: {}", outlives.join(" + "))),
+            },
             GenericParamDefKind::Type {
                 bounds,
                 default,
-                synthetic: _,
+                synthetic,
             } => {
-                // if *synthetic {
-                //     continue;
-                // }
-                let bounds = format_generic_bounds(&bounds);
+                // Argument-position `impl Trait` already shows up inline
+                if *synthetic {
+                    continue;
+                }
+                let bounds = format_generic_bounds(db, bounds);
                 let default = match default {
-                    Some(ty) => format!(" = {}", format_type(ty)),
-                    None => String::new(),
+                    Some(ty) if show_defaults => format!(" = {}", format_type(db, ty)),
+                    _ => String::new(),
                 };
                 out.push(format!("{name}{bounds}{default}"))
             }
-            GenericParamDefKind::Const { type_, default } => match default {
-                Some(default) => out.push(format!("const {name}: {type_:?} = {default}")),
-                None => out.push(format!("const {name}: {type_:?}")),
-            },
+            GenericParamDefKind::Const { type_, default } => {
+                // rustdoc gives the default as source text rather than a `Constant`
+                let type_ = format_type(db, type_);
+                match default {
+                    Some(default) if show_defaults => {
+                        out.push(format!("const {name}: {type_} = {default}"))
+                    }
+                    _ => out.push(format!("const {name}: {type_}")),
+                }
+            }
         }
     }
     match out.len() {
@@ -381,52 +1488,146 @@ fn format_generic_params(params: &[rustdoc_types::GenericParamDef]) -> String {
     }
 }
 
-fn format_generic_bounds(bounds: &[GenericBound]) -> String {
+/// The bounds of a param, including the leading `: `, e.g. `: Debug + Clone`.
+fn format_generic_bounds(db: &Database, bounds: &[GenericBound]) -> String {
+    match format_bound_list(db, bounds) {
+        list if list.is_empty() => list,
+        list => format!(": {list}"),
+    }
+}
+
+/// Bounds joined by `+`, e.g. `Debug + Clone`.
+fn format_bound_list(db: &Database, bounds: &[GenericBound]) -> String {
     let mut out = vec![];
     for bound in bounds {
         match &bound {
             GenericBound::TraitBound {
                 trait_,
-                generic_params: _, // TODO: support HRTBs
+                generic_params,
                 modifier,
             } => {
-                let trait_ = &trait_.name;
+                let is_sized = trait_.name.rsplit("::").next() == Some("Sized");
+                let trait_ = format_trait_bound(db, trait_, generic_params);
                 let modifier = match modifier {
                     TraitBoundModifier::None => "",
+                    // `?Sized` is the only relaxed bound Rust accepts, so
+                    // others are left out of the output
+                    TraitBoundModifier::Maybe if !is_sized => {
+                        db.warn(Warning::InvalidMaybeBound { name: trait_ });
+                        continue;
+                    }
                     TraitBoundModifier::Maybe => "?",
+                    TraitBoundModifier::MaybeConst if db.options().bracketed_const => "[const] ",
                     TraitBoundModifier::MaybeConst => "~const ",
                 };
                 out.push(format!("{modifier}{trait_}"));
             }
-            GenericBound::Outlives(_) => continue, // TODO: support lifetimes
+            GenericBound::Outlives(lifetime) => match format_outlives(db, lifetime) {
+                Some(lifetime) => out.push(lifetime),
+                None => continue,
+            },
         };
     }
-    match out.len() {
-        0 => String::new(),
-        _ => format!(": {}", out.join(" + ")),
+    out.join(" + ")
+}
+
+/// A trait used as a bound, including its generic args and any `for<'a>`
+/// quantifier, e.g. `for<'a> Fn(&'a str) -> &'a str`.
+fn format_trait_bound(
+    db: &Database,
+    trait_: &rustdoc_types::Path,
+    generic_params: &[rustdoc_types::GenericParamDef],
+) -> String {
+    let hrtb = format_hrtb(db, generic_params);
+    let name = format_trait_name(db, trait_);
+    let args = match &trait_.args {
+        Some(args) => format_generic_args(db, args),
+        None => String::new(),
+    };
+    format!("{hrtb}{name}{args}")
+}
+
+fn format_function_pointer(db: &Database, ptr: &rustdoc_types::FunctionPointer) -> String {
+    let hrtb = format_hrtb(db, &ptr.generic_params);
+    let is_unsafe = if ptr.header.unsafe_ { "unsafe " } else { "" };
+    let abi = format_abi(&ptr.header.abi);
+    let args: Vec<_> = ptr
+        .decl
+        .inputs
+        .iter()
+        .map(|(_, ty)| format_type(db, ty))
+        .collect();
+    let output = match &ptr.decl.output {
+        Some(ty) => format!(" -> {}", format_type(db, ty)),
+        None => String::new(),
+    };
+    format!("{hrtb}{is_unsafe}{abi}fn({}){output}", args.join(", "))
+}
+
+/// A lifetime used in a bound. Named lifetimes are dropped when elided, as
+/// their params aren't rendered; `'static` always stays.
+fn format_outlives(db: &Database, lifetime: &str) -> Option<String> {
+    match db.options().elide_lifetimes && lifetime != "'static" {
+        true => None,
+        false => Some(lifetime.to_string()),
     }
 }
 
-fn format_where_bounds(predicates: &[WherePredicate]) -> String {
+/// The `for<'a> ` quantifier of a higher-ranked bound, including its trailing
+/// space. Elided lifetimes have nothing left to quantify over.
+fn format_hrtb(db: &Database, params: &[rustdoc_types::GenericParamDef]) -> String {
+    if params.is_empty() || db.options().elide_lifetimes {
+        return String::new();
+    }
+    let params: Vec<_> = params.iter().map(|p| p.name.as_str()).collect();
+    format!("for<{}> ", params.join(", "))
+}
+
+/// The name of a trait used as a bound, which is fully-qualified when the
+/// `qualified_paths` option is set.
+fn format_trait_name(db: &Database, trait_: &rustdoc_types::Path) -> String {
+    match db.options().qualified_paths {
+        true => db
+            .find_path(&trait_.id)
+            .unwrap_or_else(|| trait_.name.clone()),
+        false => trait_.name.clone(),
+    }
+}
+
+fn format_where_bounds(db: &Database, predicates: &[WherePredicate]) -> String {
+    let coalesced;
+    let predicates = match db.options().coalesce_where_bounds {
+        true => {
+            coalesced = coalesce_where_predicates(predicates);
+            &coalesced
+        }
+        false => predicates,
+    };
     let mut out = vec![];
     for pred in predicates {
         match pred {
             WherePredicate::BoundPredicate {
                 type_,
                 bounds,
-                generic_params: _, // TODO: HRTBs
+                generic_params,
             } => out.push(format!(
-                "{}{}",
-                format_type(type_),
-                format_generic_bounds(bounds)
+                "{}{}{}",
+                format_hrtb(db, generic_params),
+                format_type(db, type_),
+                format_generic_bounds(db, bounds)
             )),
-            WherePredicate::RegionPredicate {
-                lifetime: _,
-                bounds: _,
-            } => out.push(format!("todo: region predicate")),
-            WherePredicate::EqPredicate { lhs, rhs } => {
-                out.push(format!("{} = {}", format_type(lhs), format_term(rhs)))
+            WherePredicate::RegionPredicate { lifetime, bounds } => {
+                let bounds = format_generic_bounds(db, bounds);
+                if let (Some(lifetime), false) = (format_outlives(db, lifetime), bounds.is_empty())
+                {
+                    out.push(format!("{lifetime}{bounds}"));
+                }
             }
+            WherePredicate::EqPredicate { lhs, rhs } => out.push(format!(
+                "{} = {}",
+                format_type(db, lhs),
+                format_term(db, rhs)
+            )),
         }
     }
     match out.len() {
@@ -435,96 +1636,230 @@ fn format_where_bounds(predicates: &[WherePredicate]) -> String {
     }
 }
 
-fn format_type(ty: &Type) -> String {
+/// Merge the bounds of predicates on the same type into the first of them,
+/// skipping bounds which are repeated. Predicates with different higher-ranked
+/// lifetimes are kept apart, since their bounds can't be written together.
+fn coalesce_where_predicates(predicates: &[WherePredicate]) -> Vec<WherePredicate> {
+    let mut out: Vec<WherePredicate> = vec![];
+    for pred in predicates {
+        if let WherePredicate::BoundPredicate {
+            type_,
+            bounds,
+            generic_params,
+        } = pred
+        {
+            let existing = out.iter_mut().find_map(|prev| match prev {
+                WherePredicate::BoundPredicate {
+                    type_: prev_type,
+                    bounds: prev_bounds,
+                    generic_params: prev_params,
+                } if prev_type == type_ && prev_params == generic_params => Some(prev_bounds),
+                _ => None,
+            });
+            if let Some(existing) = existing {
+                for bound in bounds {
+                    if !existing.contains(bound) {
+                        existing.push(bound.clone());
+                    }
+                }
+                continue;
+            }
+        }
+        out.push(pred.clone());
+    }
+    out
+}
+
+/// Is this the never type? rustdoc spells it `never`, though older formats
+/// used `!`.
+fn is_never(ty: &Type) -> bool {
+    matches!(ty, Type::Primitive(name) if name == "never" || name == "!")
+}
+
+fn format_type(db: &Database, ty: &Type) -> String {
     match ty {
         Type::Generic(generic) => generic.clone(),
         Type::QualifiedPath {
             name,
-            args: _, // TODO: unsure what this is
+            args, // Only non-empty for generic associated types
             self_type,
-            trait_: _, // TODO: I believe this is `<x as trait_>` bounds?
+            trait_,
         } => {
-            format!("{}::{name}", format_type(self_type))
+            let self_type = format_type(db, self_type);
+            let args = format_generic_args(db, args);
+            // rustdoc leaves the trait name empty for shorthands like `Self::Item`
+            match trait_.name.is_empty() {
+                true => format!("{self_type}::{name}{args}"),
+                false => format!("<{self_type} as {}>::{name}{args}", format_path(db, trait_)),
+            }
         }
         Type::BorrowedRef {
             lifetime,
             mutable,
             type_,
         } => {
-            let lifetime = match lifetime {
-                Some(lt) => lt.clone(),
-                None => String::new(),
-            };
-            let mutable = if *mutable { " mut" } else { "" };
-            format!("&{lifetime}{mutable} {}", format_type(type_))
+            let lifetime = format_ref_lifetime(db, lifetime.as_deref());
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{lifetime}{mutable}{}", format_type(db, type_))
         }
+        Type::Primitive(ty) if ty == "never" => "!".to_string(),
         Type::Primitive(ty) => ty.to_owned(),
-        Type::ResolvedPath(path) => path.name.clone(),
+        Type::ResolvedPath(path) => format_path(db, path),
         Type::Tuple(data) => {
-            let output: Vec<_> = data.iter().map(|ty| format_type(ty)).collect();
-            output.join(", ")
+            let output: Vec<_> = data.iter().map(|ty| format_type(db, ty)).collect();
+            match output.len() {
+                // A one-element tuple needs a trailing comma to not be parens
+                1 => format!("({},)", output[0]),
+                _ => format!("({})", output.join(", ")),
+            }
         }
-        Type::Slice(ty) => format_type(ty),
+        Type::Slice(ty) => format!("[{}]", format_type(db, ty)),
         Type::RawPointer { mutable, type_ } => match mutable {
-            true => format!("*mut {}", format_type(type_)),
-            false => format!("*const {}", format_type(type_)),
+            true => format!("*mut {}", format_type(db, type_)),
+            false => format!("*const {}", format_type(db, type_)),
         },
-        Type::FunctionPointer(_ptr) => format!("<todo: fn pointer>"),
+        Type::FunctionPointer(ptr) => format_function_pointer(db, ptr),
         Type::DynTrait(dyn_trait) => {
             let traits: Vec<_> = dyn_trait
                 .traits
                 .iter()
-                .map(|t| t.trait_.name.clone())
+                .map(|t| format_trait_bound(db, &t.trait_, &t.generic_params))
                 .collect();
             format!("dyn {}", traits.join(" + "))
         }
-        Type::ImplTrait(bounds) => format!("impl {}", format_generic_bounds(&bounds)),
-        Type::Array { type_, len } => format!("[{}; {len}]", format_type(type_)),
+        Type::ImplTrait(bounds) => format!("impl {}", format_bound_list(db, bounds)),
+        Type::Array { type_, len } => format!("[{}; {len}]", format_type(db, type_)),
         ty => format!("todo format type: {ty:?}>"),
     }
 }
 
-fn format_impl(impl_: rustdoc_types::Impl) -> String {
+fn format_path(db: &Database, path: &rustdoc_types::Path) -> String {
+    match &path.args {
+        Some(args) => format!("{}{}", path.name, format_generic_args(db, args)),
+        None => path.name.clone(),
+    }
+}
+
+fn format_generic_args(db: &Database, args: &GenericArgs) -> String {
+    match args {
+        GenericArgs::AngleBracketed { args, bindings } => {
+            let mut out: Vec<_> = args
+                .iter()
+                .map(|arg| match arg {
+                    GenericArg::Lifetime(_) if db.options().elide_lifetimes => "'_".to_string(),
+                    GenericArg::Lifetime(lt) => lt.clone(),
+                    GenericArg::Type(ty) => format_type(db, ty),
+                    GenericArg::Const(c) => format_constant(c),
+                    GenericArg::Infer => "_".to_string(),
+                })
+                .collect();
+            for binding in bindings {
+                let name = &binding.name;
+                let args = format_generic_args(db, &binding.args);
+                match &binding.binding {
+                    TypeBindingKind::Equality(term) => {
+                        out.push(format!("{name}{args} = {}", format_term(db, term)))
+                    }
+                    TypeBindingKind::Constraint(bounds) => {
+                        out.push(format!("{name}{args}{}", format_generic_bounds(db, bounds)))
+                    }
+                }
+            }
+            match out.len() {
+                0 => String::new(),
+                _ => format!("<{}>", out.join(", ")),
+            }
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            let inputs: Vec<_> = inputs.iter().map(|ty| format_type(db, ty)).collect();
+            let output = match output {
+                Some(ty) => format!(" -> {}", format_type(db, ty)),
+                None => String::new(),
+            };
+            format!("({}){output}", inputs.join(", "))
+        }
+    }
+}
+
+fn format_impl(db: &Database, impl_: rustdoc_types::Impl, assoc_consts: &[String]) -> String {
     let is_unsafe = match impl_.is_unsafe {
-        true => "",
-        false => "unsafe ",
+        true => "unsafe ",
+        false => "",
     };
-    let trait_ = match impl_.trait_ {
-        Some(trait_) => format!("{} for ", trait_.name),
+    let negative = if impl_.negative { "!" } else { "" };
+    let trait_ = match &impl_.trait_ {
+        Some(trait_) => format!("{negative}{} for ", format_path(db, trait_)),
         None => String::new(),
     };
-    let ty = format_type(&impl_.for_);
-    let params = format_generic_params(&impl_.generics.params);
-    let where_bounds = format_where_bounds(&impl_.generics.where_predicates);
-    format!("{is_unsafe}impl{params} {trait_} {ty} {where_bounds} {{}}")
+    let marker = match (impl_.synthetic, &impl_.blanket_impl) {
+        (true, _) => " // synthetic",
+        (false, Some(_)) => " // blanket",
+        (false, None) => "",
+    };
+    let ty = format_type(db, &impl_.for_);
+    let params = format_generic_params(db, &impl_.generics.params);
+    let where_bounds = format_where_bounds(db, &impl_.generics.where_predicates);
+    let body = match assoc_consts.len() {
+        0 => "{}".to_string(),
+        _ => format!("{{ {} }}", assoc_consts.join(" ")),
+    };
+    format!("{is_unsafe}impl{params} {trait_}{ty}{where_bounds} {body}{marker}")
+}
+
+/// The associated consts defined in an impl, e.g. `const ID: u32 = 3;`.
+fn format_impl_consts(db: &Database, items: &[rustdoc_types::Id]) -> Vec<String> {
+    db.find_assoc_items(items)
+        .into_iter()
+        .filter_map(|item| match (&item.inner, &item.name) {
+            (ItemEnum::AssocConst { type_, default }, Some(name)) => {
+                Some(format_assoc_const(db, name, type_, default.as_deref()))
+            }
+            _ => None,
+        })
+        .collect()
 }
 
-fn format_term(term: &Term) -> String {
+fn format_term(db: &Database, term: &Term) -> String {
     match term {
-        Term::Type(ty) => format_type(ty),
+        Term::Type(ty) => format_type(db, ty),
         Term::Constant(c) => format_constant(c),
     }
 }
 
-fn format_constant(_c: &rustdoc_types::Constant) -> String {
-    format!("todo: format constants")
+fn format_constant(c: &rustdoc_types::Constant) -> String {
+    let is_path = c
+        .expr
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+    match c.value.as_deref() {
+        // rustdoc elides expressions it can't print, but may know the value
+        Some(value) if c.expr == "_" => value.to_string(),
+        _ if c.is_literal || is_path || c.expr.starts_with('{') => c.expr.clone(),
+        // Anything more complex needs braces to be a valid const arg
+        _ => format!("{{ {} }}", c.expr),
+    }
 }
 
 /// What is the stability of this item?
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum Stability {
     /// The item is stable
     Stable,
     /// The item is unstable
+    #[default]
     Unstable,
+    /// The item is stable, but deprecated
+    Deprecated,
 }
 impl Stability {
-    /// Returns `true` if the stability is [`Stable`].
+    /// Returns `true` if the stability is [`Stable`] or [`Deprecated`], since
+    /// deprecated items are still part of the stable API.
     ///
     /// [`Stable`]: Stability::Stable
+    /// [`Deprecated`]: Stability::Deprecated
     #[must_use]
     pub fn is_stable(&self) -> bool {
-        matches!(self, Self::Stable)
+        matches!(self, Self::Stable | Self::Deprecated)
     }
 
     /// Returns `true` if the stability is [`Unstable`].
@@ -534,6 +1869,14 @@ impl Stability {
     pub fn is_unstable(&self) -> bool {
         matches!(self, Self::Unstable)
     }
+
+    /// Returns `true` if the stability is [`Deprecated`].
+    ///
+    /// [`Deprecated`]: Stability::Deprecated
+    #[must_use]
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self, Self::Deprecated)
+    }
 }
 
 impl std::fmt::Display for Stability {
@@ -541,16 +1884,168 @@ impl std::fmt::Display for Stability {
         match self {
             Self::Stable => write!(f, "stable"),
             Self::Unstable => write!(f, "unstable"),
+            Self::Deprecated => write!(f, "deprecated"),
         }
     }
 }
 
-fn parse_stability(attrs: &[String]) -> Stability {
-    let mut val = Stability::Unstable;
-    for attr in attrs {
-        if attr.contains("#[stable") {
-            val = Stability::Stable;
+/// Who can access this item?
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Visibility {
+    /// The item is `pub`
+    Public,
+    /// No visibility was written, meaning the item is private, or it's in a
+    /// trait or impl and inherits its visibility from there
+    #[default]
+    Default,
+    /// The item is `pub(crate)`
+    Crate,
+    /// The item is visible in some module, e.g. `pub(super)`
+    Restricted,
+}
+
+impl Visibility {
+    /// Returns `true` if the visibility is [`Public`].
+    ///
+    /// [`Public`]: Visibility::Public
+    #[must_use]
+    pub fn is_public(&self) -> bool {
+        matches!(self, Self::Public)
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public => write!(f, "pub"),
+            Self::Default => write!(f, "default"),
+            Self::Crate => write!(f, "pub(crate)"),
+            Self::Restricted => write!(f, "restricted"),
         }
     }
-    val
+}
+
+impl From<&rustdoc_types::Visibility> for Visibility {
+    fn from(visibility: &rustdoc_types::Visibility) -> Self {
+        match visibility {
+            rustdoc_types::Visibility::Public => Self::Public,
+            rustdoc_types::Visibility::Default => Self::Default,
+            rustdoc_types::Visibility::Crate => Self::Crate,
+            rustdoc_types::Visibility::Restricted { .. } => Self::Restricted,
+        }
+    }
+}
+
+fn is_derived(attrs: &[String]) -> bool {
+    attrs.iter().any(|attr| attr == "#[automatically_derived]")
+}
+
+fn is_hidden(attrs: &[String]) -> bool {
+    attrs.iter().any(|attr| attr == "#[doc(hidden)]")
+}
+
+fn is_documented(docs: Option<&str>) -> bool {
+    docs.is_some_and(|docs| !docs.trim().is_empty())
+}
+
+/// The docs up to the first blank line, as written.
+fn doc_paragraph(docs: Option<&str>) -> Option<String> {
+    let paragraph = docs?.trim().split("\n\n").next()?.trim_end();
+    match paragraph.is_empty() {
+        true => None,
+        false => Some(paragraph.to_string()),
+    }
+}
+
+/// The first line of the docs, with emphasis markers like `*` and `__`
+/// stripped. Underscores inside words, as in `snake_case`, and anything
+/// inside a code span, like `*const T`, are kept.
+fn doc_summary(docs: Option<&str>) -> Option<String> {
+    let line = docs?.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let chars: Vec<char> = line.chars().collect();
+    let is_alphanumeric = |i: Option<usize>| {
+        i.and_then(|i| chars.get(i))
+            .is_some_and(|c| c.is_alphanumeric())
+    };
+    let mut in_code = false;
+    let summary = chars
+        .iter()
+        .enumerate()
+        .filter(|(i, c)| match c {
+            '`' => {
+                in_code = !in_code;
+                true
+            }
+            _ if in_code => true,
+            '*' => false,
+            '_' => is_alphanumeric(i.checked_sub(1)) && is_alphanumeric(Some(i + 1)),
+            _ => true,
+        })
+        .map(|(_, c)| c)
+        .collect();
+    Some(summary)
+}
+
+/// Items without a `#[stable]` or `#[unstable]` attribute are assumed to be
+/// unstable. Stable items which rustdoc marks as deprecated are `Deprecated`.
+fn parse_stability(item: &rustdoc_types::Item) -> Stability {
+    let mut stable = false;
+    let mut unstable = false;
+    for attr in &item.attrs {
+        match attr_name(attr) {
+            Some("stable") => stable = true,
+            Some("unstable") => unstable = true,
+            _ => {}
+        }
+    }
+    let stability = match (stable, unstable) {
+        (true, false) => Stability::Stable,
+        _ => Stability::Unstable,
+    };
+    with_deprecation(stability, item)
+}
+
+/// Mark a stable stability as `Deprecated` if the item is deprecated, so that
+/// it agrees with `Item::is_deprecated`.
+fn with_deprecation(stability: Stability, item: &rustdoc_types::Item) -> Stability {
+    match (stability, item.deprecation.is_some()) {
+        (Stability::Stable, true) => Stability::Deprecated,
+        (stability, _) => stability,
+    }
+}
+
+/// The value of a key in an item's `#[stable(..)]` or `#[unstable(..)]`
+/// attribute, e.g. `rust1` for the `feature` of `#[stable(feature = "rust1")]`.
+fn stability_value(attrs: &[String], key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| matches!(attr_name(attr), Some("stable") | Some("unstable")))
+        .find_map(|attr| attr_value(attr, key))
+}
+
+/// The tracking issue of an unstable item, if it has one.
+fn tracking_issue(attrs: &[String]) -> Option<String> {
+    stability_value(attrs, "issue").filter(|issue| issue != "none")
+}
+
+/// The value of a `key = "value"` pair in an attribute's arguments.
+fn attr_value(attr: &str, key: &str) -> Option<String> {
+    let (_, args) = attr.split_once('(')?;
+    let (args, _) = args.rsplit_once(')')?;
+    args.split(',').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        match name.trim() == key {
+            true => Some(value.trim().trim_matches('"').to_string()),
+            false => None,
+        }
+    })
+}
+
+/// The path of an attribute, e.g. `stable` for `#[stable(feature = "..")]`.
+fn attr_name(attr: &str) -> Option<&str> {
+    let attr = attr.strip_prefix("#[")?;
+    let end = attr
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(attr.len());
+    Some(&attr[..end])
 }
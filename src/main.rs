@@ -1,191 +1,332 @@
+use rustdoc_denormalize::analyze;
 use rustdoc_denormalize::Crate;
+use rustdoc_denormalize::CrateStats;
 use rustdoc_denormalize::Item;
-use rustdoc_denormalize::Stability;
+use rustdoc_denormalize::Options;
+use rustdoc_denormalize::Stats;
 use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use structopt::clap;
 use structopt::StructOpt;
 
-mod analyze;
+/// The rustdoc JSON files read when no `--input` is given.
+const DEFAULT_INPUTS: [&str; 3] = ["assets/core.json", "assets/alloc.json", "assets/std.json"];
 
 #[derive(structopt::StructOpt)]
-enum Opts {
+struct Opts {
+    /// Print warnings about incomplete output to stderr
+    #[structopt(long)]
+    warn: bool,
+    /// Exit with an error and list the offenders if any item is unstable
+    #[structopt(long)]
+    fail_on_unstable: bool,
+    /// Exit with an error and list the offenders if any item is undocumented
+    #[structopt(long)]
+    fail_on_undocumented: bool,
+    /// Include synthetic auto trait impls and blanket impls, marked as such
+    #[structopt(long)]
+    show_auto: bool,
+    /// Reuse the parsed output cached next to each rustdoc JSON file
+    #[structopt(long)]
+    cache: bool,
+    /// Write the output to a file instead of stdout
+    #[structopt(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// A rustdoc JSON file to read, which can be repeated. Defaults to the
+    /// core, alloc and std files in `assets/`
+    #[structopt(long = "input", parse(from_os_str), number_of_values = 1)]
+    inputs: Vec<PathBuf>,
+    #[structopt(subcommand)]
+    cmd: Command,
+}
+
+#[derive(structopt::StructOpt)]
+enum Command {
     /// Output a table
-    Table,
+    Table {
+        /// List the methods of each trait, struct, and enum below it
+        #[structopt(long)]
+        expand: bool,
+    },
     /// Output a CSV
     Csv,
+    /// Output all items as JSON
+    Json,
+    /// Write all items to a SQLite database
+    Sqlite {
+        /// The database file to create
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
     /// Generate an analysis
-    Stats,
+    Stats {
+        /// Output the stats per kind as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+    /// List the module paths which contain items
+    Paths,
+    /// Compare the stats of several rustdoc JSON files side by side
+    Compare {
+        /// The rustdoc JSON files to compare
+        #[structopt(parse(from_os_str), required = true)]
+        files: Vec<PathBuf>,
+    },
 }
 
 fn main() -> io::Result<()> {
-    let mut krate = Crate::from_str(&fs::read_to_string("assets/core.json")?)?;
-    let mut alloc = Crate::from_str(&fs::read_to_string("assets/alloc.json")?)?;
-    let mut std = Crate::from_str(&fs::read_to_string("assets/std.json")?)?;
-
-    krate.append(&mut alloc);
-    krate.append(&mut std);
-    let table = krate.to_table();
-
-    match Opts::from_args() {
-        Opts::Table => print_table(table),
-        Opts::Csv => print_csv(krate),
-        Opts::Stats => print_stats(krate),
+    let opts = Opts::from_args();
+    let options = Options {
+        show_auto: opts.show_auto,
+        ..Options::default()
+    };
+    // The database is written to its own path, so there's nothing to output.
+    if let (Command::Sqlite { .. }, Some(_)) = (&opts.cmd, &opts.output) {
+        let message = "--output can't be used with sqlite, which writes to its own path";
+        clap::Error::with_description(message, clap::ErrorKind::ArgumentConflict).exit();
+    }
+    let mut out: Box<dyn io::Write> = match &opts.output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout()),
+    };
+    if let Command::Compare { files } = &opts.cmd {
+        print_compare(files, opts.warn, opts.cache, options, &mut out)?;
+        return out.flush();
+    }
+
+    let inputs = match opts.inputs.is_empty() {
+        true => DEFAULT_INPUTS.iter().map(PathBuf::from).collect(),
+        false => opts.inputs.clone(),
+    };
+    let mut krate = Crate::default();
+    for input in &inputs {
+        krate.append(&mut read_crate(input, opts.cache, options.clone())?);
+    }
+
+    if opts.warn {
+        print_warnings(&krate);
     }
+    if opts.fail_on_unstable && print_unstable(&krate) {
+        process::exit(1);
+    }
+    if opts.fail_on_undocumented && print_undocumented(&krate) {
+        process::exit(1);
+    }
+
+    match opts.cmd {
+        Command::Table { expand: false } => print_table(krate.to_table(), &mut out)?,
+        Command::Table { expand: true } => print_table(krate.to_expanded_table(), &mut out)?,
+        Command::Csv => print_csv(krate, &mut out)?,
+        Command::Json => print_json(krate, &mut out)?,
+        Command::Sqlite { path } => write_sqlite(krate, &path)?,
+        Command::Stats { json: true } => print_stats_json(krate, &mut out)?,
+        Command::Stats { json: false } => print_stats(krate, &mut out)?,
+        Command::Paths => print_paths(krate, &mut out)?,
+        Command::Compare { .. } => unreachable!(),
+    }
+    out.flush()
 }
 
-fn print_csv(krate: Crate) -> Result<(), io::Error> {
-    let mut writer = csv::Writer::from_writer(io::stdout());
-    krate
-        .structs
-        .into_iter()
-        .for_each(|t| writer.serialize(t).unwrap());
-    krate
-        .enums
-        .into_iter()
-        .for_each(|t| writer.serialize(t).unwrap());
-    krate
-        .traits
-        .into_iter()
-        .for_each(|t| writer.serialize(t).unwrap());
-    krate
-        .functions
-        .into_iter()
-        .for_each(|t| writer.serialize(t).unwrap());
-    krate
-        .impls
-        .into_iter()
-        .for_each(|t| writer.serialize(t).unwrap());
-    Ok(())
+fn print_warnings(krate: &Crate) {
+    for warning in krate.validate() {
+        eprintln!("warning: {warning}");
+    }
 }
 
-fn print_table(table: cli_table::TableStruct) -> Result<(), io::Error> {
-    println!("{}", table.display()?);
+/// Print the unstable items to stderr, returning whether there were any.
+fn print_unstable(krate: &Crate) -> bool {
+    let unstable = krate.unstable_items();
+    for item in &unstable {
+        eprintln!("unstable {}: {}::{}", item.kind, item.path, item.name);
+    }
+    !unstable.is_empty()
+}
+
+/// Print the undocumented items to stderr, returning whether there were any.
+fn print_undocumented(krate: &Crate) -> bool {
+    let undocumented = krate.undocumented();
+    for item in &undocumented {
+        eprintln!("undocumented {}: {}::{}", item.kind, item.path, item.name);
+    }
+    !undocumented.is_empty()
+}
+
+fn read_crate(path: impl AsRef<Path>, cache: bool, options: Options) -> io::Result<Crate> {
+    let krate = if cache {
+        Crate::from_path_cached_with_options(path, options)
+    } else {
+        let reader = io::BufReader::new(fs::File::open(path)?);
+        Crate::from_reader_with_options(reader, options)
+    };
+    krate.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn print_compare(
+    files: &[PathBuf],
+    warn: bool,
+    cache: bool,
+    options: Options,
+    out: &mut dyn io::Write,
+) -> Result<(), io::Error> {
+    let mut crates = vec![];
+    for file in files {
+        let krate = read_crate(file, cache, options.clone())?;
+        if warn {
+            print_warnings(&krate);
+        }
+        let name = match file.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => file.display().to_string(),
+        };
+        crates.push((name, krate.stats()));
+    }
+    let crates: Vec<_> = crates
+        .iter()
+        .map(|(name, stats)| (name.as_str(), *stats))
+        .collect();
+    print_table(CrateStats::compare(&crates), out)
+}
+
+fn print_csv(krate: Crate, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    krate.write_csv(out)
+}
+
+fn print_json(krate: Crate, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    writeln!(out, "{}", serde_json::to_string_pretty(&krate.to_json())?)?;
     Ok(())
 }
 
-fn print_stats(krate: Crate) -> Result<(), io::Error> {
-    let trait_stats = Stats::from_iter(krate.traits.iter().map(|t| (t.stability, t.has_generics)));
-    println!("{: <10} {trait_stats:?}", "traits");
+fn write_sqlite(krate: Crate, path: &Path) -> Result<(), io::Error> {
+    let conn = rusqlite::Connection::open(path).map_err(io::Error::other)?;
+    krate.write_sqlite(&conn).map_err(io::Error::other)
+}
 
-    let fn_stats = Stats::from_iter(
-        krate
-            .functions
-            .iter()
-            .map(|t| (t.stability, t.has_generics)),
-    );
-    println!("{: <10} {fn_stats:?}", "functions");
+fn print_paths(krate: Crate, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    for path in krate.paths() {
+        writeln!(out, "{path}")?;
+    }
+    Ok(())
+}
 
-    let struct_stats =
-        Stats::from_iter(krate.structs.iter().map(|t| (t.stability, t.has_generics)));
-    println!("{: <10} {struct_stats:?}", "structs");
+fn print_table(table: cli_table::TableStruct, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    writeln!(out, "{}", table.display()?)?;
+    Ok(())
+}
 
-    let enum_stats = Stats::from_iter(krate.enums.iter().map(|t| (t.stability, t.has_generics)));
-    println!("{: <10} {enum_stats:?}", "enums");
+fn print_stats_json(krate: Crate, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    writeln!(out, "{}", serde_json::to_string_pretty(&krate.stats())?)?;
+    Ok(())
+}
 
-    let impl_stats = Stats::from_iter(krate.impls.iter().map(|t| (t.stability, t.has_generics)));
-    println!("{: <10} {impl_stats:?}", "impls");
+fn print_stats(krate: Crate, out: &mut dyn io::Write) -> Result<(), io::Error> {
+    let stats = krate.stats();
+    for (kind, kind_stats) in stats.kinds() {
+        writeln!(out, "{kind: <12} {kind_stats:?}")?;
+    }
+    let trait_stats = stats.traits;
+    let fn_stats = stats.functions;
+    let struct_stats = stats.structs;
+    let enum_stats = stats.enums;
+    let impl_stats = stats.impls;
+    let adt_stats = stats.adts();
 
-    let adt_stats = struct_stats.clone() + enum_stats.clone();
-    println!("{: <10} {adt_stats:?}", "ADTs");
+    let visibility = krate.visibility_stats();
+    writeln!(
+        out,
+        "{: <12} pub: {: >4}, pub(crate): {: >4}, restricted: {: >4}, default: {: >4}",
+        "visibility",
+        visibility.public,
+        visibility.crate_,
+        visibility.restricted,
+        visibility.default
+    )?;
 
-    println!("\n------\n");
+    writeln!(out, "\n------\n")?;
 
-    println!(
+    writeln!(
+        out,
         "traits per ADT: {:.1}",
         impl_stats.stable as f32 / adt_stats.stable as f32
-    );
+    )?;
 
-    count_const_stats("functions", &krate.functions, &fn_stats);
-    count_const_stats("structs", &krate.structs, &struct_stats);
-    count_const_stats("traits", &krate.traits, &trait_stats);
-    count_const_stats("enums", &krate.enums, &enum_stats);
-    count_const_stats("impls", &krate.impls, &impl_stats);
+    count_const_stats(out, "functions", &krate.functions, &fn_stats)?;
+    count_const_stats(out, "structs", &krate.structs, &struct_stats)?;
+    count_const_stats(out, "traits", &krate.traits, &trait_stats)?;
+    count_const_stats(out, "enums", &krate.enums, &enum_stats)?;
+    count_const_stats(out, "impls", &krate.impls, &impl_stats)?;
 
-    println!("\n------\n");
+    writeln!(out, "\n------\n")?;
 
-    count_async_stats("functions", &krate.functions, &fn_stats, |item| {
+    count_async_stats(out, "functions", &krate.functions, &fn_stats, |item| {
         !item.has_generics
-    });
-    count_async_stats("structs", &krate.structs, &struct_stats, |_item| false);
-    count_async_stats("traits", &krate.traits, &trait_stats, |_item| false);
-    count_async_stats("enums", &krate.enums, &enum_stats, |_item| false);
-    count_async_stats("impls", &krate.impls, &impl_stats, |_item| false);
+    })?;
+    count_async_stats(out, "structs", &krate.structs, &struct_stats, |_item| false)?;
+    count_async_stats(out, "traits", &krate.traits, &trait_stats, |_item| false)?;
+    count_async_stats(out, "enums", &krate.enums, &enum_stats, |_item| false)?;
+    count_async_stats(out, "impls", &krate.impls, &impl_stats, |_item| false)?;
+
+    writeln!(out, "\n------\n")?;
 
-    println!("\n------\n");
+    let result_count = analyze::count_result_returning(&krate.functions);
+    writeln!(out, "functions returning Result: {result_count}")?;
+    let option_count = analyze::count_option_returning(&krate.functions);
+    writeln!(out, "functions returning Option: {option_count}")?;
+    let never_count = analyze::count_never_returning(&krate.functions);
+    writeln!(out, "functions never returning: {never_count}")?;
+    let max_depth = analyze::max_generic_depth(krate.iter_items());
+    writeln!(out, "max generic depth: {max_depth}")?;
+
+    writeln!(out, "\n------\n")?;
+
+    writeln!(out, "types by trait impl count:")?;
+    for (bucket, count) in analyze::traits_per_type_histogram(&krate) {
+        writeln!(out, "{: <10} {count}", bucket.to_string())?;
+    }
+
+    writeln!(out, "\n------\n")?;
     Ok(())
 }
 
-fn count_const_stats(name: &str, items: &[Item], stats: &Stats) {
+fn count_const_stats(
+    out: &mut dyn io::Write,
+    name: &str,
+    items: &[Item],
+    stats: &Stats,
+) -> io::Result<()> {
     let (const_count, excluded) = analyze::count_const_items(items);
-    count_stats(name, "const", stats, excluded, const_count);
+    count_stats(out, name, "const", stats, excluded, const_count)
 }
 
 fn count_async_stats(
+    out: &mut dyn io::Write,
     name: &str,
     items: &[Item],
     stats: &Stats,
     should_exclude: impl FnMut(&&Item) -> bool,
-) {
+) -> io::Result<()> {
     let (async_count, excluded) = analyze::count_async_items(items, should_exclude);
-    count_stats(name, "async", stats, excluded, async_count);
+    count_stats(out, name, "async", stats, excluded, async_count)
 }
 
-fn count_stats(name: &str, kind: &str, stats: &Stats, excluded: usize, const_count: usize) {
+fn count_stats(
+    out: &mut dyn io::Write,
+    name: &str,
+    kind: &str,
+    stats: &Stats,
+    excluded: usize,
+    const_count: usize,
+) -> io::Result<()> {
     let const_maximum = stats.stable - excluded;
     let const_max_ratio = (const_maximum as f64 / stats.stable as f64) * 100.0;
     let const_ratio = (const_count as f64 / const_maximum as f64) * 100.0;
-    println!("potential {kind} {name}: {const_maximum} ({const_max_ratio:.1}%)");
-    println!("currently {kind} {name}: {const_count} ({const_ratio:.1}%)",);
-}
-
-#[derive(Clone)]
-struct Stats {
-    total: usize,
-    stable: usize,
-    unstable: usize,
-    generics: usize,
-}
-
-impl std::fmt::Debug for Stats {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "total: {: >4}, stable: {: >4}, unstable: {: >4}, generics: {: >4}",
-            &self.total, &self.stable, &self.unstable, &self.generics
-        )
-    }
-}
-
-impl Stats {
-    fn from_iter(iter: impl Iterator<Item = (Stability, bool)>) -> Self {
-        let mut this = Self {
-            total: 0,
-            stable: 0,
-            unstable: 0,
-            generics: 0,
-        };
-        for (stability, has_generics) in iter {
-            this.total += 1;
-            match stability {
-                Stability::Stable => this.stable += 1,
-                Stability::Unstable => this.unstable += 1,
-            }
-            if has_generics {
-                this.generics += 1;
-            }
-        }
-        this
-    }
-}
-
-impl std::ops::Add for Stats {
-    type Output = Stats;
-
-    fn add(mut self, rhs: Self) -> Self::Output {
-        self.total += rhs.total;
-        self.stable += rhs.stable;
-        self.unstable += rhs.unstable;
-        self.generics += rhs.generics;
-        self
-    }
+    writeln!(
+        out,
+        "potential {kind} {name}: {const_maximum} ({const_max_ratio:.1}%)"
+    )?;
+    writeln!(
+        out,
+        "currently {kind} {name}: {const_count} ({const_ratio:.1}%)",
+    )
 }
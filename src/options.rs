@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Options controlling how items are rendered into their `decl` strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Options {
+    /// Render the variants of an enum instead of eliding them as `{ .. }`.
+    pub variants: bool,
+    /// Render the fields of structs, unions and enum variants, e.g. `{ x: u8 }`
+    /// rather than `{ .. }`. Enums render their variants whenever this is set.
+    pub fields: bool,
+    /// Render the traits in bounds by their full path, e.g. `core::fmt::Debug`
+    /// rather than `Debug`, to tell apart traits which share a name.
+    pub qualified_paths: bool,
+    /// Elide lifetimes from references, and render lifetime args in paths as
+    /// the anonymous `'_`, so `&'a str` renders as `&str`.
+    pub elide_lifetimes: bool,
+    /// Render the defaults of type and const params, e.g. `S = RandomState`.
+    pub show_defaults: bool,
+    /// Include the impls rustdoc generates for auto traits and blanket impls,
+    /// marking them as `// synthetic` or `// blanket` in their `decl`.
+    pub show_auto: bool,
+    /// Render `~const` bounds in the newer `[const]` syntax, e.g.
+    /// `[const] Drop` rather than `~const Drop`.
+    pub bracketed_const: bool,
+    /// Merge where predicates which bound the same type, so `T: A, T: B`
+    /// renders as `T: A + B`.
+    pub coalesce_where_bounds: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            variants: false,
+            fields: false,
+            qualified_paths: false,
+            elide_lifetimes: false,
+            show_defaults: true,
+            show_auto: false,
+            bracketed_const: false,
+            coalesce_where_bounds: false,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use rusqlite::types::Value as SqlValue;
+use rusqlite::Connection;
+use serde_json::Value;
+
+use crate::Item;
+
+/// Write every kind of item into its own table, with one column per field.
+pub(crate) fn write(krate: &super::Crate, conn: &Connection) -> rusqlite::Result<()> {
+    // All items share the same fields, so an empty item gives us the columns
+    // even if the crate has no items at all.
+    let columns: Vec<String> = fields(&Item::default())
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+
+    // Replace the tables of an earlier run rather than failing on them.
+    let tx = conn.unchecked_transaction()?;
+    for (table, items) in krate.collections() {
+        tx.execute(&format!("DROP TABLE IF EXISTS {table}"), [])?;
+        tx.execute(
+            &format!("CREATE TABLE {table} ({})", columns.join(", ")),
+            [],
+        )?;
+        let placeholders = vec!["?"; columns.len()].join(", ");
+        let mut insert = tx.prepare(&format!("INSERT INTO {table} VALUES ({placeholders})"))?;
+        for item in items {
+            let values = fields(item).into_iter().map(|(_, value)| value);
+            insert.execute(rusqlite::params_from_iter(values))?;
+        }
+    }
+    tx.commit()
+}
+
+/// The fields of an item sorted by name, converted to SQL values.
+fn fields(item: &Item) -> Vec<(String, SqlValue)> {
+    let fields = match serde_json::to_value(item) {
+        Ok(Value::Object(fields)) => fields,
+        _ => unreachable!("items serialize to objects"),
+    };
+    fields
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::Null => SqlValue::Null,
+                Value::Bool(b) => SqlValue::Integer(b.into()),
+                Value::Number(n) => match n.as_i64() {
+                    Some(n) => SqlValue::Integer(n),
+                    None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+                },
+                Value::String(s) => SqlValue::Text(s),
+                value => SqlValue::Text(value.to_string()),
+            };
+            (name, value)
+        })
+        .collect()
+}
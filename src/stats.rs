@@ -0,0 +1,174 @@
+use std::iter::FromIterator;
+
+use cli_table::TableStruct;
+use serde::Serialize;
+
+use crate::{Item, Stability, Visibility};
+
+/// Counts of items by stability
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Stats {
+    /// The total number of items
+    pub total: usize,
+    /// The number of stable items
+    pub stable: usize,
+    /// The number of unstable items
+    pub unstable: usize,
+    /// The number of deprecated items, which are also counted as stable
+    pub deprecated: usize,
+    /// The number of items with generics
+    pub generics: usize,
+    /// The fraction of items which are stable, from `0.0` to `1.0`
+    pub stable_fraction: f64,
+}
+
+impl Stats {
+    /// Count the stability and generics of a list of items
+    pub fn from_items(items: &[Item]) -> Self {
+        items
+            .iter()
+            .map(|item| (item.stability, item.has_generics))
+            .collect()
+    }
+}
+
+impl FromIterator<(Stability, bool)> for Stats {
+    fn from_iter<I: IntoIterator<Item = (Stability, bool)>>(iter: I) -> Self {
+        let mut this = Self::default();
+        for (stability, has_generics) in iter {
+            this.total += 1;
+            match stability {
+                Stability::Stable => this.stable += 1,
+                Stability::Unstable => this.unstable += 1,
+                Stability::Deprecated => {
+                    this.stable += 1;
+                    this.deprecated += 1;
+                }
+            }
+            if has_generics {
+                this.generics += 1;
+            }
+        }
+        this.stable_fraction = fraction(this.stable, this.total);
+        this
+    }
+}
+
+impl std::fmt::Debug for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "total: {: >4}, stable: {: >4}, unstable: {: >4}, deprecated: {: >4}, generics: {: >4}",
+            &self.total, &self.stable, &self.unstable, &self.deprecated, &self.generics
+        )
+    }
+}
+
+impl std::ops::Add for Stats {
+    type Output = Stats;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.total += rhs.total;
+        self.stable += rhs.stable;
+        self.unstable += rhs.unstable;
+        self.deprecated += rhs.deprecated;
+        self.generics += rhs.generics;
+        self.stable_fraction = fraction(self.stable, self.total);
+        self
+    }
+}
+
+fn fraction(count: usize, total: usize) -> f64 {
+    match total {
+        0 => 0.0,
+        _ => count as f64 / total as f64,
+    }
+}
+
+/// Stats for each kind of item in a crate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CrateStats {
+    /// Stats for traits
+    pub traits: Stats,
+    /// Stats for functions and methods
+    pub functions: Stats,
+    /// Stats for structs
+    pub structs: Stats,
+    /// Stats for enums
+    pub enums: Stats,
+    /// Stats for unions
+    pub unions: Stats,
+    /// Stats for trait impls
+    pub impls: Stats,
+    /// Stats for type aliases
+    pub type_aliases: Stats,
+    /// Stats for constants
+    pub constants: Stats,
+    /// Stats for statics
+    pub statics: Stats,
+    /// Stats for macros
+    pub macros: Stats,
+    /// Stats for associated consts and types of inherent impls
+    pub assoc_items: Stats,
+    /// The fraction of all items which are stable, from `0.0` to `1.0`
+    pub stable_fraction: f64,
+}
+
+impl CrateStats {
+    /// Stats for all ADTs, which are structs and enums combined
+    pub fn adts(&self) -> Stats {
+        self.structs + self.enums
+    }
+
+    /// The stats of each kind, labeled by name, with ADTs last
+    pub fn kinds(&self) -> [(&'static str, Stats); 12] {
+        [
+            ("traits", self.traits),
+            ("functions", self.functions),
+            ("structs", self.structs),
+            ("enums", self.enums),
+            ("unions", self.unions),
+            ("impls", self.impls),
+            ("type_aliases", self.type_aliases),
+            ("constants", self.constants),
+            ("statics", self.statics),
+            ("macros", self.macros),
+            ("assoc_items", self.assoc_items),
+            ("ADTs", self.adts()),
+        ]
+    }
+
+    /// Output a table comparing the stats of several crates, with one column
+    /// per crate.
+    pub fn compare(crates: &[(&str, CrateStats)]) -> TableStruct {
+        crate::table::to_compare_table(crates)
+    }
+}
+
+/// Counts of items by visibility
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct VisibilityStats {
+    /// The number of `pub` items
+    pub public: usize,
+    /// The number of `pub(crate)` items
+    pub crate_: usize,
+    /// The number of items visible in some module, e.g. `pub(super)`
+    pub restricted: usize,
+    /// The number of items without a written visibility
+    pub default: usize,
+}
+
+impl FromIterator<Visibility> for VisibilityStats {
+    fn from_iter<I: IntoIterator<Item = Visibility>>(iter: I) -> Self {
+        let mut this = Self::default();
+        for visibility in iter {
+            match visibility {
+                Visibility::Public => this.public += 1,
+                Visibility::Crate => this.crate_ += 1,
+                Visibility::Restricted => this.restricted += 1,
+                Visibility::Default => this.default += 1,
+            }
+        }
+        this
+    }
+}
@@ -1,87 +1,73 @@
-use cli_table::{Cell, Style, Table, TableStruct};
-pub(crate) fn to_table(krate: &super::Crate) -> TableStruct {
-    let mut output = krate
-        .traits
-        .iter()
-        .map(|t| {
-            vec![
-                "trait".cell(),
-                format!("{}::{}", t.path, t.name).cell(),
-                t.decl.clone().cell(),
-                t.has_generics.cell(),
-                t.stability.cell(),
-                format!("{}", t.fn_count).cell(),
-            ]
-        })
-        .collect::<Vec<_>>();
+use std::collections::BTreeSet;
 
-    output.append(
-        &mut krate
-            .structs
-            .iter()
-            .map(|t| {
-                vec![
-                    "struct".cell(),
-                    format!("{}::{}", t.path, t.name).cell(),
-                    t.decl.clone().cell(),
-                    t.has_generics.cell(),
-                    t.stability.cell(),
-                    format!("{}", t.fn_count).cell(),
-                ]
-            })
-            .collect::<Vec<_>>(),
-    );
+use cli_table::{Cell, CellStruct, Style, Table, TableStruct};
 
-    output.append(
-        &mut krate
-            .enums
-            .iter()
-            .map(|t| {
-                vec![
-                    "enums".cell(),
-                    format!("{}::{}", t.path, t.name).cell(),
-                    t.decl.clone().cell(),
-                    t.has_generics.cell(),
-                    t.stability.cell(),
-                    format!("{}", t.fn_count).cell(),
-                ]
-            })
-            .collect::<Vec<_>>(),
-    );
+use crate::{CrateStats, Item};
 
-    output.append(
-        &mut krate
-            .functions
-            .iter()
-            .map(|t| {
-                vec![
-                    "function".cell(),
-                    format!("{}::{}", t.path, t.name).cell(),
-                    t.decl.clone().cell(),
-                    t.has_generics.cell(),
-                    t.stability.cell(),
-                    0.cell(),
-                ]
-            })
-            .collect::<Vec<_>>(),
-    );
+pub(crate) fn to_table(krate: &super::Crate) -> TableStruct {
+    let kinds = [
+        &krate.traits,
+        &krate.structs,
+        &krate.enums,
+        &krate.unions,
+        &krate.type_aliases,
+        &krate.constants,
+        &krate.statics,
+        &krate.macros,
+        &krate.functions,
+        &krate.assoc_items,
+        &krate.impls,
+    ];
+    let mut output = vec![];
+    for items in kinds {
+        for item in items {
+            output.push(row(item.kind, qualified_name(item), item));
+        }
+    }
+    output.table().title(vec![
+        "Kind".cell().bold(true),
+        "Name".cell().bold(true),
+        "Signature".cell().bold(true),
+        "Generics?".cell().bold(true),
+        "Stability".cell().bold(true),
+        "Methods".cell().bold(true),
+    ])
+}
 
-    output.append(
-        &mut krate
-            .impls
-            .iter()
-            .map(|t| {
-                vec![
-                    "impl".cell(),
-                    format!("{}::{}", t.path, t.name).cell(),
-                    t.decl.clone().cell(),
-                    t.has_generics.cell(),
-                    t.stability.cell(),
-                    0.cell(),
-                ]
-            })
-            .collect::<Vec<_>>(),
-    );
+/// Like `to_table`, but with each trait, struct, enum, and union followed by
+/// indented rows for its methods and associated items.
+pub(crate) fn to_expanded_table(krate: &super::Crate) -> TableStruct {
+    let parents = [&krate.traits, &krate.structs, &krate.enums, &krate.unions];
+    let mut children = BTreeSet::new();
+    let mut output = vec![];
+    for items in parents {
+        for parent in items {
+            let parent_path = qualified_name(parent);
+            output.push(row(parent.kind, parent_path.clone(), parent));
+            for method in krate.functions.iter().filter(|f| f.path == parent_path) {
+                children.insert(&method.id);
+                output.push(row("method", format!("  {}", method.name), method));
+            }
+            for assoc in krate.assoc_items.iter().filter(|a| a.path == parent_path) {
+                children.insert(&assoc.id);
+                output.push(row(assoc.kind, format!("  {}", assoc.name), assoc));
+            }
+        }
+    }
+    let rest = [
+        &krate.type_aliases,
+        &krate.constants,
+        &krate.macros,
+        &krate.statics,
+        &krate.functions,
+        &krate.assoc_items,
+        &krate.impls,
+    ];
+    for items in rest {
+        for item in items.iter().filter(|item| !children.contains(&item.id)) {
+            output.push(row(item.kind, qualified_name(item), item));
+        }
+    }
     output.table().title(vec![
         "Kind".cell().bold(true),
         "Name".cell().bold(true),
@@ -91,3 +77,41 @@ pub(crate) fn to_table(krate: &super::Crate) -> TableStruct {
         "Methods".cell().bold(true),
     ])
 }
+
+/// The path of an item including its own name, e.g. `core::fmt::Debug`.
+fn qualified_name(item: &Item) -> String {
+    format!("{}::{}", item.path, item.name)
+}
+
+fn row(kind: &str, name: String, t: &Item) -> Vec<CellStruct> {
+    vec![
+        kind.cell(),
+        name.cell(),
+        t.decl.clone().cell(),
+        t.has_generics.cell(),
+        t.stability.cell(),
+        format!("{}", t.fn_count).cell(),
+    ]
+}
+
+pub(crate) fn to_compare_table(crates: &[(&str, CrateStats)]) -> TableStruct {
+    let kinds = CrateStats::default().kinds().map(|(kind, _)| kind);
+    let output = kinds
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| {
+            let mut row = vec![kind.cell()];
+            for (_, stats) in crates {
+                let (_, stats) = stats.kinds()[i];
+                row.push(format!("{} ({} stable)", stats.total, stats.stable).cell());
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let mut title = vec!["Kind".cell().bold(true)];
+    for (name, _) in crates {
+        title.push(name.cell().bold(true));
+    }
+    output.table().title(title)
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A problem found while denormalizing a crate, meaning the output may be
+/// incomplete.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Warning {
+    /// An item was skipped because it has no name
+    Unnamed {
+        /// The rustdoc ID of the skipped item
+        id: String,
+    },
+    /// An item was rendered with an empty declaration
+    EmptyDecl {
+        /// What kind of item is this?
+        #[serde(deserialize_with = "crate::item::deserialize_kind")]
+        kind: crate::item::Kind,
+        /// The full path of the item
+        path: String,
+    },
+    /// The trait an impl implements could not be found in the crate
+    UnresolvedTrait {
+        /// The name of the implemented trait
+        name: String,
+        /// The signature of the impl
+        decl: String,
+    },
+    /// A `?Trait` bound for a trait other than `Sized`, which isn't valid
+    /// Rust, was left out of the output
+    InvalidMaybeBound {
+        /// The name of the relaxed trait
+        name: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unnamed { id } => write!(f, "skipped item `{id}` without a name"),
+            Self::EmptyDecl { kind, path } => write!(f, "{kind} `{path}` has an empty signature"),
+            Self::UnresolvedTrait { name, decl } => {
+                write!(f, "could not resolve trait `{name}` for `{decl}`")
+            }
+            Self::InvalidMaybeBound { name } => {
+                write!(f, "skipped invalid relaxed bound `?{name}`")
+            }
+        }
+    }
+}
@@ -0,0 +1,3092 @@
+use std::collections::HashMap;
+
+use rustdoc_denormalize::{
+    analyze, AnyItem, Crate, CrateStats, Options, ParseError, Stability, VisibilityStats, Warning,
+    SCHEMA_VERSION,
+};
+use rustdoc_types::{
+    Abi, Enum, FnDecl, Function, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Header, Id, Impl, ItemEnum, ItemKind, ItemSummary, Module, Path,
+    Struct, StructKind, Trait, TraitBoundModifier, Type, TypeBinding, TypeBindingKind, Union,
+    Variant, VariantKind, Visibility,
+};
+
+const STABLE: &str = r#"#[stable(feature = "rust1", since = "1.0.0")]"#;
+
+/// A synthetic rustdoc crate with a single root module named `fixture`.
+struct Fixture {
+    krate: rustdoc_types::Crate,
+    next_id: usize,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let root = Id("0:0".to_string());
+        let mut this = Self {
+            krate: rustdoc_types::Crate {
+                root: root.clone(),
+                crate_version: None,
+                includes_private: false,
+                index: HashMap::new(),
+                paths: HashMap::new(),
+                external_crates: HashMap::new(),
+                format_version: rustdoc_types::FORMAT_VERSION,
+            },
+            next_id: 1,
+        };
+        let module = ItemEnum::Module(Module {
+            is_crate: true,
+            items: vec![],
+            is_stripped: false,
+        });
+        this.insert_with_id(root.clone(), Some("fixture"), module);
+        this.add_path(&root, &["fixture"], ItemKind::Module);
+        this
+    }
+
+    /// Insert a stable item into the index without exporting it from the
+    /// root module. Use this for impls, methods, and other nested items.
+    fn insert(&mut self, name: Option<&str>, inner: ItemEnum) -> Id {
+        let id = self.reserve_id();
+        self.insert_with_id(id.clone(), name, inner);
+        id
+    }
+
+    /// Insert a stable item into the index and export it from the root module.
+    fn export(&mut self, name: &str, inner: ItemEnum) -> Id {
+        let id = self.insert(Some(name), inner);
+        self.export_id(&id);
+        id
+    }
+
+    /// Export an already inserted item from the root module.
+    fn export_id(&mut self, id: &Id) {
+        let root = self.krate.root.clone();
+        match &mut self.item_mut(&root).inner {
+            ItemEnum::Module(module) => module.items.push(id.clone()),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Allocate a fresh id, for items which need to refer to each other.
+    fn reserve_id(&mut self) -> Id {
+        let id = Id(format!("0:{}", self.next_id));
+        self.next_id += 1;
+        id
+    }
+
+    fn insert_with_id(&mut self, id: Id, name: Option<&str>, inner: ItemEnum) {
+        let item = rustdoc_types::Item {
+            id: id.clone(),
+            crate_id: 0,
+            name: name.map(String::from),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: vec![STABLE.to_string()],
+            deprecation: None,
+            inner,
+        };
+        self.krate.index.insert(id, item);
+    }
+
+    /// Register a path for an id, e.g. for an external trait.
+    fn add_path(&mut self, id: &Id, path: &[&str], kind: ItemKind) {
+        let summary = ItemSummary {
+            crate_id: 0,
+            path: path.iter().map(|s| s.to_string()).collect(),
+            kind,
+        };
+        self.krate.paths.insert(id.clone(), summary);
+    }
+
+    /// Write the fixture to a JSON file in the temp dir, e.g. for passing to
+    /// the binary.
+    fn write_temp(&self, name: &str) -> std::path::PathBuf {
+        let file = format!("rustdoc-denormalize-{}-{name}.json", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        std::fs::write(&path, serde_json::to_vec(&self.krate).unwrap()).unwrap();
+        path
+    }
+
+    fn item_mut(&mut self, id: &Id) -> &mut rustdoc_types::Item {
+        self.krate.index.get_mut(id).unwrap()
+    }
+
+    fn parse(&self) -> Crate {
+        self.parse_with_options(Options::default())
+    }
+
+    fn parse_with_options(&self, options: Options) -> Crate {
+        let json = serde_json::to_string(&self.krate).unwrap();
+        Crate::from_str_with_options(&json, options).unwrap()
+    }
+}
+
+fn generics() -> Generics {
+    Generics {
+        params: vec![],
+        where_predicates: vec![],
+    }
+}
+
+fn function(
+    inputs: Vec<(&str, rustdoc_types::Type)>,
+    output: Option<rustdoc_types::Type>,
+) -> Function {
+    Function {
+        decl: FnDecl {
+            inputs: inputs
+                .into_iter()
+                .map(|(name, ty)| (name.to_string(), ty))
+                .collect(),
+            output,
+            c_variadic: false,
+        },
+        generics: generics(),
+        header: Header {
+            const_: false,
+            unsafe_: false,
+            async_: false,
+            abi: Abi::Rust,
+        },
+        has_body: true,
+    }
+}
+
+fn resolved_path(name: &str, id: &Id) -> Type {
+    Type::ResolvedPath(Path {
+        name: name.to_string(),
+        id: id.clone(),
+        args: None,
+    })
+}
+
+fn primitive(name: &str) -> Type {
+    Type::Primitive(name.to_string())
+}
+
+/// An external type with the given type args, e.g. `Vec<u8>`.
+fn generic_type(name: &str, args: Vec<Type>) -> Type {
+    Type::ResolvedPath(Path {
+        name: name.to_string(),
+        id: Id(format!("1:{name}")),
+        args: Some(Box::new(GenericArgs::AngleBracketed {
+            args: args.into_iter().map(GenericArg::Type).collect(),
+            bindings: vec![],
+        })),
+    })
+}
+
+/// An associated type projection, e.g. `<T as Iterator>::Item`. An empty
+/// trait name renders the shorthand form, e.g. `T::Item`.
+fn qualified_path(self_type: Type, trait_: &str, name: &str) -> Type {
+    Type::QualifiedPath {
+        name: name.to_string(),
+        args: Box::new(GenericArgs::AngleBracketed {
+            args: vec![],
+            bindings: vec![],
+        }),
+        self_type: Box::new(self_type),
+        trait_: trait_path(trait_, &Id(format!("1:{trait_}"))),
+    }
+}
+
+fn strukt(impls: Vec<Id>) -> ItemEnum {
+    ItemEnum::Struct(Struct {
+        kind: StructKind::Unit,
+        generics: generics(),
+        impls,
+    })
+}
+
+fn enum_(variants: Vec<Id>, impls: Vec<Id>) -> ItemEnum {
+    ItemEnum::Enum(Enum {
+        generics: generics(),
+        variants_stripped: false,
+        variants,
+        impls,
+    })
+}
+
+fn trait_(items: Vec<Id>) -> ItemEnum {
+    ItemEnum::Trait(Trait {
+        is_auto: false,
+        is_unsafe: false,
+        items,
+        generics: generics(),
+        bounds: vec![],
+        implementations: vec![],
+    })
+}
+
+fn impl_(trait_: Option<Path>, for_: Type, items: Vec<Id>) -> ItemEnum {
+    ItemEnum::Impl(Impl {
+        is_unsafe: false,
+        generics: generics(),
+        provided_trait_methods: vec![],
+        trait_,
+        for_,
+        items,
+        negative: false,
+        synthetic: false,
+        blanket_impl: None,
+    })
+}
+
+fn trait_path(name: &str, id: &Id) -> Path {
+    Path {
+        name: name.to_string(),
+        id: id.clone(),
+        args: None,
+    }
+}
+
+fn trait_bound(trait_: Path) -> GenericBound {
+    GenericBound::TraitBound {
+        trait_,
+        generic_params: vec![],
+        modifier: TraitBoundModifier::None,
+    }
+}
+
+fn type_param(name: &str, bounds: Vec<GenericBound>) -> GenericParamDef {
+    GenericParamDef {
+        name: name.to_string(),
+        kind: GenericParamDefKind::Type {
+            bounds,
+            default: None,
+            synthetic: false,
+        },
+    }
+}
+
+/// A fixture with one trait (with a method), a struct implementing it, an
+/// enum, and a free function.
+fn mixed_fixture() -> Fixture {
+    let mut fixture = Fixture::new();
+    let method = fixture.insert(Some("call"), ItemEnum::Function(function(vec![], None)));
+    let trait_id = fixture.export("Call", trait_(vec![method]));
+    let struct_id = fixture.reserve_id();
+    let path = trait_path("Call", &trait_id);
+    let for_ = resolved_path("Foo", &struct_id);
+    let impl_id = fixture.insert(None, impl_(Some(path), for_, vec![]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+    fixture.export("Bar", enum_(vec![], vec![]));
+    fixture.export("baz", ItemEnum::Function(function(vec![], None)));
+    fixture
+}
+
+/// Render a table to a string without any terminal colors.
+fn render_table(table: cli_table::TableStruct) -> String {
+    let table = table.color_choice(cli_table::ColorChoice::Never);
+    table.display().unwrap().to_string()
+}
+
+/// Parse a fixture exporting a single free function and return its decl.
+fn function_decl(name: &str, fn_: Function) -> String {
+    let mut fixture = Fixture::new();
+    fixture.export(name, ItemEnum::Function(fn_));
+    let krate = fixture.parse();
+    assert_eq!(krate.functions.len(), 1);
+    krate.functions[0].decl.clone()
+}
+
+#[test]
+fn renders_unwind_abis() {
+    let mut fn_ = function(vec![], None);
+    fn_.header.abi = Abi::C { unwind: true };
+    assert_eq!(
+        function_decl("f", fn_.clone()),
+        r#"extern "C-unwind" fn f() { .. }"#
+    );
+
+    fn_.header.abi = Abi::System { unwind: true };
+    assert_eq!(
+        function_decl("f", fn_.clone()),
+        r#"extern "system-unwind" fn f() { .. }"#
+    );
+
+    fn_.header.abi = Abi::C { unwind: false };
+    assert_eq!(function_decl("f", fn_), r#"extern "C" fn f() { .. }"#);
+}
+
+#[test]
+fn into_items_contains_every_kind() {
+    let krate = mixed_fixture().parse();
+    let items = krate.into_items();
+    assert_eq!(items.len(), 6);
+
+    let count = |f: fn(&AnyItem) -> bool| items.iter().filter(|item| f(item)).count();
+    assert_eq!(count(|item| matches!(item, AnyItem::Trait(_))), 1);
+    assert_eq!(count(|item| matches!(item, AnyItem::Struct(_))), 1);
+    assert_eq!(count(|item| matches!(item, AnyItem::Enum(_))), 1);
+    assert_eq!(count(|item| matches!(item, AnyItem::Impl(_))), 1);
+    // The trait method and the free function
+    assert_eq!(count(|item| matches!(item, AnyItem::Function(_))), 2);
+}
+
+#[test]
+fn append_dedup_matches_batch_dedup() {
+    fn other_fixture() -> Fixture {
+        let mut fixture = Fixture::new();
+        fixture.export("qux", ItemEnum::Function(function(vec![], None)));
+        fixture.export("Quux", strukt(vec![]));
+        fixture
+    }
+
+    // Batch: append everything, then sort and dedup once at the end.
+    let mut batch = mixed_fixture().parse();
+    for mut krate in [mixed_fixture().parse(), other_fixture().parse()] {
+        batch.traits.append(&mut krate.traits);
+        batch.structs.append(&mut krate.structs);
+        batch.enums.append(&mut krate.enums);
+        batch.impls.append(&mut krate.impls);
+        batch.functions.append(&mut krate.functions);
+    }
+    for items in [
+        &mut batch.traits,
+        &mut batch.structs,
+        &mut batch.enums,
+        &mut batch.impls,
+        &mut batch.functions,
+    ] {
+        items.sort();
+        items.dedup();
+    }
+
+    // Incremental: merge one crate at a time.
+    let mut incremental = mixed_fixture().parse();
+    incremental.append_dedup(&mut mixed_fixture().parse());
+    incremental.append_dedup(&mut other_fixture().parse());
+
+    assert_eq!(incremental, batch);
+    assert_eq!(incremental.functions.len(), 3);
+    assert_eq!(incremental.structs.len(), 2);
+}
+
+#[test]
+fn skips_unnamed_variants() {
+    let mut fixture = Fixture::new();
+    let variant = ItemEnum::Variant(Variant {
+        kind: VariantKind::Plain,
+        discriminant: None,
+    });
+    let named = fixture.insert(Some("Red"), variant.clone());
+    let unnamed = fixture.insert(None, variant);
+    fixture.export("Color", enum_(vec![named, unnamed], vec![]));
+
+    let options = Options {
+        variants: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.enums[0].decl, "enum Color { Red }");
+}
+
+#[test]
+fn renders_variant_discriminants() {
+    let mut fixture = Fixture::new();
+    let mut variant = |name: &str, discriminant: Option<(&str, &str)>| {
+        let variant = ItemEnum::Variant(Variant {
+            kind: VariantKind::Plain,
+            discriminant: discriminant.map(|(expr, value)| rustdoc_types::Discriminant {
+                expr: expr.to_string(),
+                value: value.to_string(),
+            }),
+        });
+        fixture.insert(Some(name), variant)
+    };
+    let variants = vec![
+        variant("Red", Some(("1", "1"))),
+        variant("Green", None),
+        variant("Blue", Some(("1 << 2", "4"))),
+    ];
+    fixture.export("Color", enum_(variants, vec![]));
+
+    let options = Options {
+        variants: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.enums[0].decl,
+        "enum Color { Red = 1, Green, Blue = 1 << 2 }"
+    );
+
+    // Variants are elided by default.
+    let krate = fixture.parse();
+    assert_eq!(krate.enums[0].decl, "enum Color { .. }");
+}
+
+#[test]
+fn renders_tuple_struct_fields() {
+    let mut fixture = Fixture::new();
+    let field = fixture.insert(Some("0"), ItemEnum::StructField(primitive("u8")));
+    let strukt = ItemEnum::Struct(Struct {
+        kind: StructKind::Tuple(vec![Some(field), None]),
+        generics: generics(),
+        impls: vec![],
+    });
+    fixture.export("Pair", strukt);
+
+    let options = Options {
+        fields: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.structs[0].decl, "struct Pair(u8, _);");
+
+    // Fields are elided by default.
+    let krate = fixture.parse();
+    assert_eq!(krate.structs[0].decl, "struct Pair(..);");
+}
+
+#[test]
+fn renders_named_struct_fields() {
+    let mut fixture = Fixture::new();
+    let x = fixture.insert(Some("x"), ItemEnum::StructField(primitive("u8")));
+    let vec = generic_type("Vec", vec![primitive("u8")]);
+    let bytes = fixture.insert(Some("bytes"), ItemEnum::StructField(vec));
+    let strukt = ItemEnum::Struct(Struct {
+        kind: StructKind::Plain {
+            fields: vec![x, bytes],
+            fields_stripped: true,
+        },
+        generics: generics(),
+        impls: vec![],
+    });
+    fixture.export("Point", strukt);
+
+    let options = Options {
+        fields: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.structs[0].decl,
+        "struct Point { x: u8, bytes: Vec<u8>, .. }"
+    );
+
+    let krate = fixture.parse();
+    assert_eq!(krate.structs[0].decl, "struct Point { .. }");
+}
+
+#[test]
+fn renders_union_fields() {
+    let mut fixture = Fixture::new();
+    let int = fixture.insert(Some("int"), ItemEnum::StructField(primitive("u32")));
+    let float = fixture.insert(Some("float"), ItemEnum::StructField(primitive("f32")));
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![int, float],
+        impls: vec![],
+    });
+    fixture.export("Bits", union);
+
+    let options = Options {
+        fields: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.unions[0].decl, "union Bits { int: u32, float: f32 }");
+
+    let krate = fixture.parse();
+    assert_eq!(krate.unions[0].decl, "union Bits { .. }");
+}
+
+#[test]
+fn skips_unnamed_fields() {
+    let mut fixture = Fixture::new();
+    let int = fixture.insert(Some("int"), ItemEnum::StructField(primitive("u32")));
+    let unnamed = fixture.insert(None, ItemEnum::StructField(primitive("f32")));
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![int, unnamed],
+        impls: vec![],
+    });
+    fixture.export("Bits", union);
+
+    let options = Options {
+        fields: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.unions[0].decl, "union Bits { int: u32 }");
+}
+
+#[test]
+fn renders_enum_variant_fields() {
+    let variant = |kind| {
+        ItemEnum::Variant(Variant {
+            kind,
+            discriminant: None,
+        })
+    };
+    let mut fixture = Fixture::new();
+    let empty = fixture.insert(Some("Empty"), variant(VariantKind::Plain));
+    let field = fixture.insert(Some("0"), ItemEnum::StructField(primitive("u32")));
+    let tuple = VariantKind::Tuple(vec![Some(field)]);
+    let tuple = fixture.insert(Some("Tuple"), variant(tuple));
+    let x = fixture.insert(Some("x"), ItemEnum::StructField(primitive("bool")));
+    let named = VariantKind::Struct {
+        fields: vec![x],
+        fields_stripped: false,
+    };
+    let named = fixture.insert(Some("Named"), variant(named));
+    fixture.export("Shape", enum_(vec![empty, tuple, named], vec![]));
+
+    let options = Options {
+        fields: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.enums[0].decl,
+        "enum Shape { Empty, Tuple(u32), Named { x: bool } }"
+    );
+
+    // Rendering only the variants still elides their fields.
+    let options = Options {
+        variants: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.enums[0].decl,
+        "enum Shape { Empty, Tuple(..), Named { .. } }"
+    );
+}
+
+#[test]
+fn renders_qualified_bound_paths() {
+    let mut fixture = Fixture::new();
+    let debug = Id("1:100".to_string());
+    fixture.add_path(&debug, &["core", "fmt", "Debug"], ItemKind::Trait);
+
+    let mut fn_ = function(vec![("t", Type::Generic("T".to_string()))], None);
+    let bound = trait_bound(trait_path("Debug", &debug));
+    fn_.generics.params.push(type_param("T", vec![bound]));
+    fixture.export("f", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions[0].decl, "fn f<T: Debug>(t: T) { .. }");
+
+    let options = Options {
+        qualified_paths: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f<T: core::fmt::Debug>(t: T) { .. }"
+    );
+}
+
+#[test]
+fn counts_result_and_option_returning_functions() {
+    let mut fixture = Fixture::new();
+    let result = resolved_path("io::Result", &Id("1:1".to_string()));
+    let option = resolved_path("Option", &Id("1:2".to_string()));
+    let plain = Type::Generic("T".to_string());
+    fixture.export("a", ItemEnum::Function(function(vec![], Some(result))));
+    fixture.export("b", ItemEnum::Function(function(vec![], Some(option))));
+    fixture.export("c", ItemEnum::Function(function(vec![], Some(plain))));
+    fixture.export("d", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(analyze::count_result_returning(&krate.functions), 1);
+    assert_eq!(analyze::count_option_returning(&krate.functions), 1);
+}
+
+#[test]
+fn validate_warns_about_unresolved_traits() {
+    let mut fixture = Fixture::new();
+    let struct_id = fixture.reserve_id();
+    let external = trait_path("Debug", &Id("1:100".to_string()));
+    let for_ = resolved_path("Foo", &struct_id);
+    let impl_id = fixture.insert(None, impl_(Some(external), for_, vec![]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+
+    let warnings = fixture.parse().validate();
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(
+        &warnings[0],
+        Warning::UnresolvedTrait { name, .. } if name == "Debug"
+    ));
+
+    // Traits defined in the crate itself resolve fine.
+    assert!(mixed_fixture().parse().validate().is_empty());
+}
+
+#[test]
+fn renders_parameterized_type_defaults() {
+    let mut fixture = Fixture::new();
+    let vec = generic_type("Vec", vec![primitive("u8")]);
+    let mut param = type_param("T", vec![]);
+    if let GenericParamDefKind::Type { default, .. } = &mut param.kind {
+        *default = Some(vec);
+    }
+    let mut strukt = Struct {
+        kind: StructKind::Unit,
+        generics: generics(),
+        impls: vec![],
+    };
+    strukt.generics.params.push(param);
+    fixture.export("Foo", ItemEnum::Struct(strukt));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.structs[0].decl, "struct Foo<T = Vec<u8>>;");
+}
+
+#[test]
+fn renders_impls_on_tuples_and_arrays() {
+    let mut fixture = Fixture::new();
+    let default = trait_path("Default", &Id("1:1".to_string()));
+    let tuple = Type::Tuple(vec![primitive("u8"), primitive("u16")]);
+    let tuple_impl = fixture.insert(None, impl_(Some(default), tuple, vec![]));
+    let primitive_tuple = rustdoc_types::Primitive {
+        name: "tuple".to_string(),
+        impls: vec![tuple_impl],
+    };
+    fixture.export("tuple", ItemEnum::Primitive(primitive_tuple));
+
+    let t = Type::Generic("T".to_string());
+    let mut as_ref = trait_path("AsRef", &Id("1:2".to_string()));
+    as_ref.args = Some(Box::new(GenericArgs::AngleBracketed {
+        args: vec![GenericArg::Type(Type::Slice(Box::new(t.clone())))],
+        bindings: vec![],
+    }));
+    let array = Type::Array {
+        type_: Box::new(t),
+        len: "4".to_string(),
+    };
+    let mut array_impl = impl_(Some(as_ref), array, vec![]);
+    if let ItemEnum::Impl(impl_) = &mut array_impl {
+        impl_.generics.params.push(type_param("T", vec![]));
+    }
+    let array_impl = fixture.insert(None, array_impl);
+    let primitive_array = rustdoc_types::Primitive {
+        name: "array".to_string(),
+        impls: vec![array_impl],
+    };
+    fixture.export("array", ItemEnum::Primitive(primitive_array));
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate.impls.iter().map(|i| i.decl.as_str()).collect();
+    assert_eq!(decls.len(), 2);
+    assert!(decls.contains(&"impl Default for (u8, u16) {}"));
+    assert!(decls.contains(&"impl<T> AsRef<[T]> for [T; 4] {}"));
+}
+
+#[test]
+fn content_hash_is_stable_and_tracks_decls() {
+    let krate = mixed_fixture().parse();
+    assert_eq!(krate.content_hash(), mixed_fixture().parse().content_hash());
+    for (left, right) in krate.iter_items().zip(mixed_fixture().parse().iter_items()) {
+        assert_eq!(left.content_hash(), right.content_hash());
+    }
+
+    // Counts don't affect the hash
+    let mut counted = mixed_fixture().parse();
+    counted.structs[0].fn_count += 1;
+    assert_eq!(counted.content_hash(), krate.content_hash());
+
+    // But signatures do
+    let mut fixture = mixed_fixture();
+    let arg = ("x", primitive("u8"));
+    fixture.export("extra", ItemEnum::Function(function(vec![arg], None)));
+    let changed = fixture.parse();
+    assert_ne!(changed.content_hash(), krate.content_hash());
+
+    let mut edited = mixed_fixture().parse();
+    edited.functions[0].decl.push(' ');
+    assert_ne!(
+        edited.functions[0].content_hash(),
+        krate.functions[0].content_hash()
+    );
+}
+
+#[test]
+fn compares_stats_per_crate() {
+    let mut fixture = Fixture::new();
+    fixture.export("a", ItemEnum::Function(function(vec![], None)));
+    let unstable = fixture.export("b", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&unstable).attrs.clear();
+    let small = fixture.parse().stats();
+    let mixed = mixed_fixture().parse().stats();
+    assert_eq!(small.functions.total, 2);
+    assert_eq!(small.functions.stable, 1);
+    assert_eq!(mixed.functions.total, 2);
+    assert_eq!(mixed.adts().total, 2);
+
+    let table = CrateStats::compare(&[("small", small), ("mixed", mixed)]);
+    let table = render_table(table);
+    let header = table.lines().nth(1).unwrap();
+    assert!(header.contains("small") && header.contains("mixed"));
+    assert!(header.find("small") < header.find("mixed"));
+
+    let row = |kind: &str| {
+        let row = table.lines().find(|line| line.contains(kind)).unwrap();
+        let cells: Vec<_> = row.split('|').map(str::trim).collect();
+        cells[2..4].join(",")
+    };
+    assert_eq!(row("functions"), "2 (1 stable),2 (2 stable)");
+    assert_eq!(row("structs"), "0 (0 stable),1 (1 stable)");
+}
+
+#[test]
+fn renders_impls_on_primitives() {
+    let mut fixture = Fixture::new();
+    let display = trait_path("Display", &Id("1:1".to_string()));
+    let impl_id = fixture.insert(None, impl_(Some(display), primitive("u8"), vec![]));
+    let u8_ = rustdoc_types::Primitive {
+        name: "u8".to_string(),
+        impls: vec![impl_id],
+    };
+    fixture.export("u8", ItemEnum::Primitive(u8_));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.impls.len(), 1);
+    assert_eq!(krate.impls[0].decl, "impl Display for u8 {}");
+    assert_eq!(krate.impls[0].for_type, "u8");
+}
+
+#[test]
+fn public_contract_keeps_stable_supported_items() {
+    let mut fixture = Fixture::new();
+    fixture.export("kept", ItemEnum::Function(function(vec![], None)));
+    let hidden = fixture.export("hidden", ItemEnum::Function(function(vec![], None)));
+    fixture
+        .item_mut(&hidden)
+        .attrs
+        .push("#[doc(hidden)]".to_string());
+    let unstable = fixture.export("unstable", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&unstable).attrs.clear();
+    let deprecated = fixture.export("deprecated", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&deprecated).deprecation = Some(rustdoc_types::Deprecation {
+        since: Some("1.0.0".to_string()),
+        note: None,
+    });
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions.len(), 4);
+    let contract = krate.public_contract();
+    let names: Vec<_> = contract.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["kept"]);
+}
+
+#[test]
+fn stats_carry_stable_fractions() {
+    let mut fixture = Fixture::new();
+    for name in ["a", "b", "c", "d"] {
+        fixture.export(name, ItemEnum::Function(function(vec![], None)));
+    }
+    let unstable = fixture.export("Foo", strukt(vec![]));
+    fixture.item_mut(&unstable).attrs.clear();
+    let stats = fixture.parse().stats();
+    assert_eq!(stats.functions.stable_fraction, 1.0);
+    assert_eq!(stats.structs.stable_fraction, 0.0);
+    assert_eq!(stats.traits.stable_fraction, 0.0);
+    assert_eq!(stats.stable_fraction, 0.8);
+
+    let json = serde_json::to_value(stats).unwrap();
+    assert_eq!(json["stable_fraction"], 0.8);
+    assert_eq!(json["functions"]["stable_fraction"], 1.0);
+}
+
+#[test]
+fn renders_where_predicates_on_qualified_paths() {
+    let t = Type::Generic("T".to_string());
+    let mut fn_ = function(vec![("t", t.clone())], None);
+    fn_.generics.params.push(type_param("T", vec![]));
+    let debug = trait_bound(trait_path("Debug", &Id("1:1".to_string())));
+    fn_.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::BoundPredicate {
+            type_: qualified_path(t, "Iterator", "Item"),
+            bounds: vec![debug],
+            generic_params: vec![],
+        });
+    assert_eq!(
+        function_decl("f", fn_),
+        "fn f<T>(t: T) where <T as Iterator>::Item: Debug { .. }"
+    );
+}
+
+#[test]
+fn coalesces_where_predicates_on_the_same_type() {
+    let t = Type::Generic("T".to_string());
+    let mut fn_ = function(vec![("t", t.clone())], None);
+    fn_.generics.params.push(type_param("T", vec![]));
+    for name in ["Clone", "Debug", "Clone"] {
+        let bound = trait_bound(trait_path(name, &Id(format!("1:{name}"))));
+        fn_.generics
+            .where_predicates
+            .push(rustdoc_types::WherePredicate::BoundPredicate {
+                type_: t.clone(),
+                bounds: vec![bound],
+                generic_params: vec![],
+            });
+    }
+    let mut fixture = Fixture::new();
+    fixture.export("f", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f<T>(t: T) where T: Clone, T: Debug, T: Clone { .. }"
+    );
+
+    let options = Options {
+        coalesce_where_bounds: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f<T>(t: T) where T: Clone + Debug { .. }"
+    );
+}
+
+#[test]
+fn counts_items_by_visibility() {
+    let mut fixture = Fixture::new();
+    let visibilities = vec![
+        Visibility::Public,
+        Visibility::Public,
+        Visibility::Crate,
+        Visibility::Restricted {
+            parent: Id("0:0".to_string()),
+            path: "crate::fixture".to_string(),
+        },
+    ];
+    for (i, visibility) in visibilities.into_iter().enumerate() {
+        let id = fixture.export(&format!("f{i}"), ItemEnum::Function(function(vec![], None)));
+        fixture.item_mut(&id).visibility = visibility;
+    }
+
+    let stats = fixture.parse().visibility_stats();
+    let expected = VisibilityStats {
+        public: 2,
+        crate_: 1,
+        restricted: 1,
+        default: 0,
+    };
+    assert_eq!(stats, expected);
+}
+
+#[test]
+fn renders_iterator_style_signatures() {
+    let mut fixture = Fixture::new();
+    let self_ty = Type::Generic("Self".to_string());
+    let receiver = Type::BorrowedRef {
+        lifetime: None,
+        mutable: true,
+        type_: Box::new(self_ty.clone()),
+    };
+    let output = generic_type("Option", vec![qualified_path(self_ty, "", "Item")]);
+    let mut next = function(vec![("self", receiver)], Some(output));
+    next.has_body = false;
+    let next = fixture.insert(Some("next"), ItemEnum::Function(next));
+    fixture.export("Iterator", trait_(vec![next]));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn next(&mut self) -> Option<Self::Item>;"
+    );
+}
+
+#[test]
+fn counts_items_by_stability() {
+    let mut fixture = mixed_fixture();
+    let unstable = fixture.export("Unstable", strukt(vec![]));
+    fixture.item_mut(&unstable).attrs.clear();
+    let unstable = fixture.export("unstable", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&unstable).attrs.clear();
+
+    // The mixed fixture has six stable items, one of which is an impl
+    assert_eq!(fixture.parse().counts_by_stability(), (6, 2));
+}
+
+#[test]
+fn renders_qualified_dyn_traits() {
+    let mut fixture = Fixture::new();
+    let error = Id("1:100".to_string());
+    fixture.add_path(&error, &["core", "error", "Error"], ItemKind::Trait);
+    let send = Id("1:101".to_string());
+    fixture.add_path(&send, &["core", "marker", "Send"], ItemKind::Trait);
+    let poly_trait = |name: &str, id: &Id| rustdoc_types::PolyTrait {
+        trait_: trait_path(name, id),
+        generic_params: vec![],
+    };
+    let dyn_ = Type::DynTrait(rustdoc_types::DynTrait {
+        traits: vec![poly_trait("Error", &error), poly_trait("Send", &send)],
+        lifetime: None,
+    });
+    let arg = Type::BorrowedRef {
+        lifetime: None,
+        mutable: false,
+        type_: Box::new(dyn_),
+    };
+    fixture.export("f", ItemEnum::Function(function(vec![("e", arg)], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions[0].decl, "fn f(e: &dyn Error + Send) { .. }");
+
+    let options = Options {
+        qualified_paths: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f(e: &dyn core::error::Error + core::marker::Send) { .. }"
+    );
+}
+
+#[test]
+fn renders_bounds_on_type_aliases() {
+    let mut fixture = Fixture::new();
+    let hash = trait_bound(trait_path("Hash", &Id("1:1".to_string())));
+    let k = Type::Generic("K".to_string());
+    let v = Type::Generic("V".to_string());
+    let alias = rustdoc_types::Typedef {
+        type_: generic_type("HashMap", vec![k, v]),
+        generics: Generics {
+            params: vec![type_param("K", vec![hash]), type_param("V", vec![])],
+            where_predicates: vec![],
+        },
+    };
+    fixture.export("Map", ItemEnum::Typedef(alias));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.type_aliases.len(), 1);
+    assert!(krate.type_aliases[0].has_generics);
+    assert_eq!(
+        krate.type_aliases[0].decl,
+        "type Map<K: Hash, V> = HashMap<K, V>;"
+    );
+
+    for table in [krate.to_table(), krate.to_expanded_table()] {
+        let table = render_table(table);
+        let row = table
+            .lines()
+            .find(|line| line.contains("type Map<K: Hash, V> = HashMap<K, V>;"))
+            .expect("type alias row");
+        assert!(row.contains("| type "));
+        assert!(row.contains("fixture::Map"));
+    }
+}
+
+#[test]
+fn extracts_inherent_associated_consts() {
+    let mut fixture = Fixture::new();
+    let assoc = ItemEnum::AssocConst {
+        type_: primitive("usize"),
+        default: Some("4".to_string()),
+    };
+    let const_id = fixture.insert(Some("SIZE"), assoc);
+    let method = fixture.insert(Some("len"), ItemEnum::Function(function(vec![], None)));
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![const_id, method]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.structs[0].fn_count, 1);
+    assert_eq!(krate.assoc_items.len(), 1);
+    let assoc = &krate.assoc_items[0];
+    assert_eq!(assoc.kind, "const");
+    assert_eq!(assoc.path, "fixture::Foo");
+    assert_eq!(assoc.decl, "const SIZE: usize = 4;");
+    assert_eq!(krate.stats().assoc_items.total, 1);
+
+    let mut csv = vec![];
+    krate.write_csv(&mut csv).unwrap();
+    assert!(String::from_utf8(csv)
+        .unwrap()
+        .contains("const SIZE: usize = 4;"));
+
+    let table = render_table(krate.to_table());
+    assert!(table.contains("fixture::Foo::SIZE"));
+    let table = render_table(krate.to_expanded_table());
+    let lines: Vec<_> = table.lines().collect();
+    let parent = lines
+        .iter()
+        .position(|line| line.contains("fixture::Foo "))
+        .expect("struct row");
+    assert!(lines[parent + 4].contains("|   SIZE "), "{}", table);
+    assert_eq!(table.matches("const SIZE").count(), 1);
+}
+
+#[test]
+fn elides_lifetimes() {
+    let mut fixture = Fixture::new();
+    let str_ref = Type::BorrowedRef {
+        lifetime: Some("'a".to_string()),
+        mutable: false,
+        type_: Box::new(primitive("str")),
+    };
+    let mut fn_ = function(vec![("x", str_ref.clone())], Some(str_ref));
+    fn_.generics.params.push(GenericParamDef {
+        name: "'a".to_string(),
+        kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+    });
+    fixture.export("f", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f<'a>(x: &'a str) -> &'a str { .. }"
+    );
+
+    let options = Options {
+        elide_lifetimes: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.functions[0].decl, "fn f(x: &str) -> &str { .. }");
+}
+
+#[test]
+fn renders_function_qualifiers_in_order() {
+    let cases = vec![
+        ((false, false, false), "fn f() { .. }"),
+        ((true, false, false), "const fn f() { .. }"),
+        ((false, true, false), "unsafe fn f() { .. }"),
+        ((false, false, true), "async fn f() { .. }"),
+        ((true, true, false), "const unsafe fn f() { .. }"),
+        ((true, false, true), "const async fn f() { .. }"),
+        ((false, true, true), "async unsafe fn f() { .. }"),
+        ((true, true, true), "const async unsafe fn f() { .. }"),
+    ];
+    for ((const_, unsafe_, async_), expected) in cases {
+        let mut fn_ = function(vec![], None);
+        fn_.header.const_ = const_;
+        fn_.header.unsafe_ = unsafe_;
+        fn_.header.async_ = async_;
+        assert_eq!(function_decl("f", fn_), expected);
+    }
+}
+
+#[test]
+fn renders_projections_in_return_position() {
+    let self_ty = Type::Generic("Self".to_string());
+    let output = qualified_path(self_ty.clone(), "Iterator", "Item");
+    let fn_ = function(vec![("self", self_ty)], Some(output));
+    assert_eq!(
+        function_decl("first", fn_),
+        "fn first(self) -> <Self as Iterator>::Item { .. }"
+    );
+}
+
+#[test]
+fn omits_generic_defaults() {
+    let mut fixture = Fixture::new();
+    let random_state = resolved_path("RandomState", &Id("1:1".to_string()));
+    let mut strukt = rustdoc_types::Struct {
+        kind: StructKind::Plain {
+            fields: vec![],
+            fields_stripped: true,
+        },
+        generics: generics(),
+        impls: vec![],
+    };
+    strukt.generics.params = vec![
+        type_param("K", vec![]),
+        type_param("V", vec![]),
+        GenericParamDef {
+            name: "S".to_string(),
+            kind: GenericParamDefKind::Type {
+                bounds: vec![],
+                default: Some(random_state),
+                synthetic: false,
+            },
+        },
+    ];
+    fixture.export("HashMap", ItemEnum::Struct(strukt));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.structs[0].decl,
+        "struct HashMap<K, V, S = RandomState> { .. }"
+    );
+
+    let options = Options {
+        show_defaults: false,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.structs[0].decl, "struct HashMap<K, V, S> { .. }");
+}
+
+#[test]
+fn indexes_items_by_signature() {
+    let mut fixture = Fixture::new();
+    let self_ty = || Type::Generic("Self".to_string());
+    fixture.export("new", ItemEnum::Function(function(vec![], Some(self_ty()))));
+    fixture.export(
+        "empty",
+        ItemEnum::Function(function(vec![], Some(self_ty()))),
+    );
+    fixture.export("clear", ItemEnum::Function(function(vec![], None)));
+    fixture.export("type", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    let index = krate.signature_index();
+    let names: Vec<_> = index["fn() -> Self"].iter().map(|i| &i.name).collect();
+    assert_eq!(names, vec!["new", "empty"]);
+    let names: Vec<_> = index["fn()"].iter().map(|i| &i.name).collect();
+    assert_eq!(names, vec!["clear", "type"]);
+}
+
+#[test]
+fn counts_traits_excluding_auto_traits() {
+    let mut fixture = Fixture::new();
+    let mut send = trait_(vec![]);
+    if let ItemEnum::Trait(trait_) = &mut send {
+        trait_.is_auto = true;
+    }
+    fixture.export("Send", send);
+    fixture.export("Debug", trait_(vec![]));
+    fixture.export("Display", trait_(vec![]));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.traits.len(), 3);
+    assert_eq!(krate.trait_count_excluding_auto(), 2);
+}
+
+#[test]
+fn renders_safe_impls_without_unsafe() {
+    let krate = mixed_fixture().parse();
+    assert_eq!(krate.impls.len(), 1);
+    assert!(krate.impls[0].decl.starts_with("impl "));
+    assert_eq!(krate.impls[0].decl, "impl Call for Foo {}");
+}
+
+#[test]
+fn renders_unsafe_impls() {
+    let mut fixture = Fixture::new();
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let send = trait_path("Send", &Id("1:1".to_string()));
+    let mut send_impl = impl_(Some(send), for_.clone(), vec![]);
+    if let ItemEnum::Impl(impl_) = &mut send_impl {
+        impl_.is_unsafe = true;
+    }
+    let send_impl = fixture.insert(None, send_impl);
+    let clone = trait_path("Clone", &Id("1:2".to_string()));
+    let clone_impl = fixture.insert(None, impl_(Some(clone), for_, vec![]));
+    let foo = strukt(vec![send_impl, clone_impl]);
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), foo);
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate.impls.iter().map(|i| i.decl.as_str()).collect();
+    assert_eq!(decls.len(), 2);
+    assert!(decls.contains(&"unsafe impl Send for Foo {}"));
+    assert!(decls.contains(&"impl Clone for Foo {}"));
+}
+
+#[test]
+fn records_unsafe_auto_traits() {
+    let mut fixture = Fixture::new();
+    let mut send = trait_(vec![]);
+    if let ItemEnum::Trait(trait_) = &mut send {
+        trait_.is_auto = true;
+        trait_.is_unsafe = true;
+    }
+    fixture.export("Send", send);
+
+    let krate = fixture.parse();
+    let send = &krate.traits[0];
+    assert!(send.is_auto);
+    assert!(send.is_unsafe);
+    assert_eq!(send.decl, "unsafe auto trait Send { }");
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.serialize(send).unwrap();
+    let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    let header = csv.lines().next().unwrap();
+    assert!(header.contains(",is_auto,is_unsafe,"));
+}
+
+#[test]
+fn renders_tuples_in_return_position() {
+    let cases = vec![
+        (vec![], "fn f() -> () { .. }"),
+        (vec![primitive("u8")], "fn f() -> (u8,) { .. }"),
+        (
+            vec![primitive("u8"), primitive("u16")],
+            "fn f() -> (u8, u16) { .. }",
+        ),
+    ];
+    for (elems, expected) in cases {
+        let fn_ = function(vec![], Some(Type::Tuple(elems)));
+        assert_eq!(function_decl("f", fn_), expected);
+    }
+}
+
+#[test]
+fn measures_generic_nesting_depth() {
+    let mut fixture = Fixture::new();
+    let t = Type::Generic("T".to_string());
+    let nested = generic_type(
+        "Vec",
+        vec![generic_type("Option", vec![generic_type("Box", vec![t])])],
+    );
+    let mut fn_ = function(vec![("x", primitive("u8"))], Some(nested));
+    fn_.generics.params.push(type_param("T", vec![]));
+    fixture.export("f", ItemEnum::Function(fn_));
+    fixture.export("g", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    let depths: Vec<_> = krate
+        .functions
+        .iter()
+        .map(|f| f.max_generic_depth)
+        .collect();
+    assert_eq!(depths, vec![3, 0]);
+    assert_eq!(analyze::max_generic_depth(krate.iter_items()), 3);
+}
+
+#[test]
+fn renders_abis_on_trait_and_impl_methods() {
+    let mut fixture = Fixture::new();
+    let mut extern_fn = function(vec![], None);
+    extern_fn.header.abi = Abi::C { unwind: false };
+    extern_fn.has_body = false;
+    let required = fixture.insert(Some("call"), ItemEnum::Function(extern_fn.clone()));
+    fixture.export("Call", trait_(vec![required]));
+
+    extern_fn.has_body = true;
+    let method = fixture.insert(Some("run"), ItemEnum::Function(extern_fn));
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![method]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate.functions.iter().map(|f| f.decl.as_str()).collect();
+    assert!(decls.contains(&"extern \"C\" fn call();"));
+    assert!(decls.contains(&"extern \"C\" fn run() { .. }"));
+}
+
+#[test]
+fn breaks_down_trait_items() {
+    let mut fixture = Fixture::new();
+    let mut required = function(vec![], None);
+    required.has_body = false;
+    let required = fixture.insert(Some("next"), ItemEnum::Function(required));
+    let provided = fixture.insert(Some("count"), ItemEnum::Function(function(vec![], None)));
+    let assoc = ItemEnum::AssocConst {
+        type_: primitive("usize"),
+        default: None,
+    };
+    let assoc = fixture.insert(Some("MAX"), assoc);
+    fixture.export("Iter", trait_(vec![required, provided, assoc]));
+
+    let krate = fixture.parse();
+    let trait_ = &krate.traits[0];
+    assert_eq!(trait_.fn_count, 2);
+    assert_eq!(trait_.required_fn_count(), 1);
+    assert_eq!(trait_.provided_fn_count, 1);
+    assert_eq!(trait_.assoc_count, 1);
+}
+
+#[test]
+fn renders_slice_and_array_arguments() {
+    let slice = Type::BorrowedRef {
+        lifetime: None,
+        mutable: false,
+        type_: Box::new(Type::Slice(Box::new(Type::Generic("T".to_string())))),
+    };
+    let array = Type::Array {
+        type_: Box::new(primitive("u8")),
+        len: "16".to_string(),
+    };
+    let mut fn_ = function(vec![("xs", slice), ("key", array)], None);
+    fn_.generics.params.push(type_param("T", vec![]));
+    assert_eq!(
+        function_decl("f", fn_),
+        "fn f<T>(xs: &[T], key: [u8; 16]) { .. }"
+    );
+}
+
+#[test]
+fn renders_boxed_slices() {
+    let bytes = Type::BorrowedRef {
+        lifetime: None,
+        mutable: false,
+        type_: Box::new(Type::Slice(Box::new(primitive("u8")))),
+    };
+    let boxed = generic_type(
+        "Box",
+        vec![Type::Slice(Box::new(Type::Generic("T".to_string())))],
+    );
+    let mut fn_ = function(vec![("bytes", bytes)], Some(boxed));
+    fn_.generics.params.push(type_param("T", vec![]));
+    assert_eq!(
+        function_decl("f", fn_),
+        "fn f<T>(bytes: &[u8]) -> Box<[T]> { .. }"
+    );
+}
+
+#[test]
+fn reads_type_aliases_from_newer_format_versions() {
+    let mut fixture = Fixture::new();
+    let alias = rustdoc_types::Typedef {
+        type_: primitive("u8"),
+        generics: generics(),
+    };
+    let id = fixture.export("Byte", ItemEnum::Typedef(alias));
+    fixture.add_path(&id, &["fixture", "Byte"], ItemKind::Typedef);
+    let json = serde_json::to_string(&fixture.krate).unwrap();
+
+    let mut newer: serde_json::Value = serde_json::from_str(&json).unwrap();
+    newer["format_version"] = 25.into();
+    newer["index"][&id.0]["kind"] = "type_alias".into();
+    newer["paths"][&id.0]["kind"] = "type_alias".into();
+    let newer = serde_json::to_string(&newer).unwrap();
+
+    let native = Crate::from_str(&json).unwrap();
+    let newer = Crate::from_str(&newer).unwrap();
+    assert_eq!(native.type_aliases.len(), 1);
+    assert_eq!(native, newer);
+}
+
+#[test]
+fn renders_higher_ranked_closure_bounds() {
+    let str_ref = || Type::BorrowedRef {
+        lifetime: Some("'a".to_string()),
+        mutable: false,
+        type_: Box::new(primitive("str")),
+    };
+    let mut fn_trait = trait_path("Fn", &Id("1:1".to_string()));
+    fn_trait.args = Some(Box::new(GenericArgs::Parenthesized {
+        inputs: vec![str_ref()],
+        output: Some(str_ref()),
+    }));
+    let bound = GenericBound::TraitBound {
+        trait_: fn_trait,
+        generic_params: vec![GenericParamDef {
+            name: "'a".to_string(),
+            kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+        }],
+        modifier: TraitBoundModifier::None,
+    };
+    let f = Type::Generic("F".to_string());
+    let mut fn_ = function(vec![("f", f)], None);
+    fn_.generics.params.push(type_param("F", vec![bound]));
+    assert_eq!(
+        function_decl("apply", fn_),
+        "fn apply<F: for<'a> Fn(&'a str) -> &'a str>(f: F) { .. }"
+    );
+}
+
+#[test]
+fn renders_const_equality_predicates() {
+    let t = Type::Generic("T".to_string());
+    let mut fn_ = function(vec![("t", t.clone())], None);
+    fn_.generics.params.push(type_param("T", vec![]));
+    fn_.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::EqPredicate {
+            lhs: qualified_path(t, "Array", "LEN"),
+            rhs: rustdoc_types::Term::Constant(rustdoc_types::Constant {
+                type_: primitive("usize"),
+                expr: "4".to_string(),
+                value: Some("4usize".to_string()),
+                is_literal: true,
+            }),
+        });
+    assert_eq!(
+        function_decl("f", fn_),
+        "fn f<T>(t: T) where <T as Array>::LEN = 4 { .. }"
+    );
+}
+
+#[test]
+fn renders_raw_pointers() {
+    let ptr = |mutable, ty| Type::RawPointer {
+        mutable,
+        type_: Box::new(ty),
+    };
+    let t = || Type::Generic("T".to_string());
+    let cases = vec![
+        (ptr(false, primitive("u8")), "*const u8"),
+        (ptr(true, t()), "*mut T"),
+        (
+            ptr(false, Type::Slice(Box::new(primitive("u8")))),
+            "*const [u8]",
+        ),
+        (ptr(false, ptr(true, t())), "*const *mut T"),
+    ];
+    for (ty, expected) in cases {
+        let mut fn_ = function(vec![("p", ty)], None);
+        fn_.generics.params.push(type_param("T", vec![]));
+        let expected = format!("fn f<T>(p: {expected}) {{ .. }}");
+        assert_eq!(function_decl("f", fn_), expected);
+    }
+}
+
+#[test]
+fn lists_unstable_items() {
+    let mut fixture = mixed_fixture();
+    let id = fixture.export("nightly", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&id).attrs.clear();
+
+    let krate = fixture.parse();
+    let unstable: Vec<_> = krate.unstable_items().iter().map(|i| &i.name).collect();
+    assert_eq!(unstable, vec!["nightly"]);
+    assert!(mixed_fixture().parse().unstable_items().is_empty());
+}
+
+/// Run the binary with the given args.
+fn run_cli(args: &[&std::ffi::OsStr]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_rustdoc-denormalize"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn fails_on_unstable_items() {
+    let mut fixture = mixed_fixture();
+    let id = fixture.export("nightly", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&id).attrs.clear();
+    let unstable = fixture.write_temp("unstable");
+    let stable = mixed_fixture().write_temp("stable");
+
+    let output = run_cli(&[
+        "--fail-on-unstable".as_ref(),
+        "--input".as_ref(),
+        unstable.as_os_str(),
+        "paths".as_ref(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr, "unstable function: fixture::nightly\n");
+    assert!(output.stdout.is_empty());
+
+    let output = run_cli(&[
+        "--fail-on-unstable".as_ref(),
+        "--input".as_ref(),
+        stable.as_os_str(),
+        "paths".as_ref(),
+    ]);
+    std::fs::remove_file(&unstable).unwrap();
+    std::fs::remove_file(&stable).unwrap();
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "fixture\n");
+}
+
+#[test]
+fn writes_output_to_a_file() {
+    let input = mixed_fixture().write_temp("output");
+    let output = input.with_extension("csv");
+    let result = run_cli(&[
+        "--input".as_ref(),
+        input.as_os_str(),
+        "--output".as_ref(),
+        output.as_os_str(),
+        "csv".as_ref(),
+    ]);
+    assert!(result.status.success());
+    assert!(result.stdout.is_empty());
+    let csv = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    assert!(csv.starts_with("kind,"));
+    assert_eq!(csv.lines().count(), 7);
+
+    // The sqlite subcommand writes to its own path, so `--output` is rejected
+    // before anything is created.
+    let db = input.with_extension("db");
+    let result = run_cli(&[
+        "--input".as_ref(),
+        input.as_os_str(),
+        "--output".as_ref(),
+        output.as_os_str(),
+        "sqlite".as_ref(),
+        db.as_os_str(),
+    ]);
+    std::fs::remove_file(&input).unwrap();
+    assert!(!result.status.success());
+    assert!(!output.exists());
+    assert!(!db.exists());
+}
+
+#[test]
+fn lists_distinct_module_paths() {
+    let mut fixture = mixed_fixture();
+    for name in ["io", "fs"] {
+        let item = fixture.insert(Some("f"), ItemEnum::Function(function(vec![], None)));
+        let module = ItemEnum::Module(Module {
+            is_crate: false,
+            items: vec![item],
+            is_stripped: false,
+        });
+        let id = fixture.export(name, module);
+        fixture.add_path(&id, &["fixture", name], ItemKind::Module);
+    }
+
+    let paths: Vec<_> = fixture.parse().paths().into_iter().collect();
+    assert_eq!(paths, vec!["fixture", "fixture::fs", "fixture::io"]);
+}
+
+#[test]
+fn renders_function_pointers() {
+    let fn_ptr = |inputs, output, unsafe_, abi| {
+        let mut fn_ = function(inputs, output);
+        fn_.header.unsafe_ = unsafe_;
+        fn_.header.abi = abi;
+        Type::FunctionPointer(Box::new(rustdoc_types::FunctionPointer {
+            decl: fn_.decl,
+            generic_params: vec![],
+            header: fn_.header,
+        }))
+    };
+    let bytes = Type::RawPointer {
+        mutable: false,
+        type_: Box::new(primitive("u8")),
+    };
+    let cases = vec![
+        (fn_ptr(vec![], None, false, Abi::Rust), "fn()"),
+        (
+            fn_ptr(
+                vec![("_", primitive("u8"))],
+                Some(primitive("bool")),
+                false,
+                Abi::Rust,
+            ),
+            "fn(u8) -> bool",
+        ),
+        (
+            fn_ptr(vec![("_", bytes)], None, true, Abi::C { unwind: false }),
+            "unsafe extern \"C\" fn(*const u8)",
+        ),
+    ];
+    for (ty, expected) in cases {
+        let fn_ = function(vec![("f", ty)], None);
+        let expected = format!("fn call(f: {expected}) {{ .. }}");
+        assert_eq!(function_decl("call", fn_), expected);
+    }
+}
+
+#[test]
+fn renders_higher_ranked_where_predicates() {
+    let t = Type::Generic("T".to_string());
+    let mut fn_trait = trait_path("Fn", &Id("1:1".to_string()));
+    fn_trait.args = Some(Box::new(GenericArgs::Parenthesized {
+        inputs: vec![Type::BorrowedRef {
+            lifetime: Some("'a".to_string()),
+            mutable: false,
+            type_: Box::new(primitive("str")),
+        }],
+        output: None,
+    }));
+    let mut fn_ = function(vec![("t", t.clone())], None);
+    fn_.generics.params.push(type_param("T", vec![]));
+    fn_.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::BoundPredicate {
+            type_: t,
+            bounds: vec![trait_bound(fn_trait)],
+            generic_params: vec![GenericParamDef {
+                name: "'a".to_string(),
+                kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+            }],
+        });
+    assert_eq!(
+        function_decl("f", fn_),
+        "fn f<T>(t: T) where for<'a> T: Fn(&'a str) { .. }"
+    );
+}
+
+#[test]
+fn renders_constants() {
+    let constant = |expr: &str, value: Option<&str>, is_literal| {
+        GenericArg::Const(rustdoc_types::Constant {
+            type_: primitive("usize"),
+            expr: expr.to_string(),
+            value: value.map(String::from),
+            is_literal,
+        })
+    };
+    let cases = vec![
+        (constant("4", Some("4usize"), true), "Buf<4>"),
+        (constant("N", None, false), "Buf<N>"),
+        (constant("N * 2", None, false), "Buf<{ N * 2 }>"),
+        (constant("_", Some("8usize"), false), "Buf<8usize>"),
+    ];
+    for (arg, expected) in cases {
+        let mut buf = resolved_path("Buf", &Id("1:1".to_string()));
+        if let Type::ResolvedPath(path) = &mut buf {
+            path.args = Some(Box::new(GenericArgs::AngleBracketed {
+                args: vec![arg],
+                bindings: vec![],
+            }));
+        }
+        let fn_ = function(vec![], Some(buf));
+        let expected = format!("fn f() -> {expected} {{ .. }}");
+        assert_eq!(function_decl("f", fn_), expected);
+    }
+}
+
+#[test]
+fn renders_lifetime_bounds() {
+    let mut fixture = Fixture::new();
+    let t = Type::Generic("T".to_string());
+    let mut fn_ = function(vec![("t", t)], None);
+    for (name, outlives) in [
+        ("'a", vec![]),
+        ("'b", vec![]),
+        ("'c", vec![]),
+        ("'d", vec!["'a"]),
+    ] {
+        fn_.generics.params.push(GenericParamDef {
+            name: name.to_string(),
+            kind: GenericParamDefKind::Lifetime {
+                outlives: outlives.into_iter().map(String::from).collect(),
+            },
+        });
+    }
+    let bound = GenericBound::Outlives("'static".to_string());
+    fn_.generics.params.push(type_param("T", vec![bound]));
+    fn_.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::RegionPredicate {
+            lifetime: "'a".to_string(),
+            bounds: vec![
+                GenericBound::Outlives("'b".to_string()),
+                GenericBound::Outlives("'c".to_string()),
+            ],
+        });
+    fixture.export("f", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f<'a, 'b, 'c, 'd: 'a, T: 'static>(t: T) where 'a: 'b + 'c { .. }"
+    );
+
+    let options = Options {
+        elide_lifetimes: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(krate.functions[0].decl, "fn f<T: 'static>(t: T) { .. }");
+}
+
+#[test]
+fn marks_generated_impls_when_shown() {
+    let mut fixture = Fixture::new();
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let send = trait_path("Send", &Id("1:1".to_string()));
+    let mut synthetic = impl_(Some(send), for_.clone(), vec![]);
+    if let ItemEnum::Impl(impl_) = &mut synthetic {
+        impl_.synthetic = true;
+    }
+    let from = trait_path("From", &Id("1:2".to_string()));
+    let mut blanket = impl_(Some(from), for_.clone(), vec![]);
+    if let ItemEnum::Impl(impl_) = &mut blanket {
+        impl_.blanket_impl = Some(Type::Generic("T".to_string()));
+    }
+    let sync = trait_path("Sync", &Id("1:3".to_string()));
+    let mut negative = impl_(Some(sync), for_, vec![]);
+    if let ItemEnum::Impl(impl_) = &mut negative {
+        impl_.negative = true;
+    }
+    let impls = vec![
+        fixture.insert(None, synthetic),
+        fixture.insert(None, blanket),
+        fixture.insert(None, negative),
+    ];
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(impls));
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate.impls.iter().map(|i| i.decl.as_str()).collect();
+    assert_eq!(decls, vec!["impl !Sync for Foo {}"]);
+
+    let options = Options {
+        show_auto: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    let decls: Vec<_> = krate.impls.iter().map(|i| i.decl.as_str()).collect();
+    assert_eq!(decls.len(), 3);
+    assert!(decls.contains(&"impl Send for Foo {} // synthetic"));
+    assert!(decls.contains(&"impl From for Foo {} // blanket"));
+    assert!(decls.contains(&"impl !Sync for Foo {}"));
+}
+
+#[test]
+fn warns_on_relaxed_bounds_other_than_sized() {
+    let maybe = |name: &str, id: &str| GenericBound::TraitBound {
+        trait_: trait_path(name, &Id(id.to_string())),
+        generic_params: vec![],
+        modifier: TraitBoundModifier::Maybe,
+    };
+    let mut fixture = Fixture::new();
+    let mut fn_ = function(vec![], None);
+    fn_.generics
+        .params
+        .push(type_param("T", vec![maybe("Sized", "1:1")]));
+    fn_.generics
+        .params
+        .push(type_param("U", vec![maybe("Clone", "1:2")]));
+    fixture.export("f", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions[0].decl, "fn f<T: ?Sized, U>() { .. }");
+    let expected = Warning::InvalidMaybeBound {
+        name: "Clone".to_string(),
+    };
+    assert_eq!(krate.warnings, vec![expected]);
+}
+
+#[test]
+fn renames_paths() {
+    let mut fixture = mixed_fixture();
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![],
+        impls: vec![],
+    });
+    fixture.export("Bits", union);
+    let constant = ItemEnum::Constant(rustdoc_types::Constant {
+        type_: primitive("u8"),
+        expr: "1".to_string(),
+        value: None,
+        is_literal: true,
+    });
+    fixture.export("ONE", constant);
+    let static_ = ItemEnum::Static(rustdoc_types::Static {
+        type_: primitive("u8"),
+        mutable: false,
+        expr: "1".to_string(),
+    });
+    fixture.export("UNIT", static_);
+    let source = "macro_rules! one {\n    () => { ... };\n}";
+    fixture.export("one", ItemEnum::Macro(source.to_string()));
+
+    let mut krate = fixture.parse();
+    krate.rename_paths(|path| match path.strip_prefix("fixture") {
+        Some(rest) => format!("std{rest}"),
+        None => path.to_string(),
+    });
+
+    let mut paths: Vec<_> = krate.iter_items().map(|i| i.path.as_str()).collect();
+    paths.sort_unstable();
+    paths.dedup();
+    assert_eq!(paths, vec!["std", "std::Call"]);
+}
+
+#[test]
+fn counts_impls_on_local_and_external_types() {
+    let mut fixture = mixed_fixture();
+    let display = trait_path("Display", &Id("1:1".to_string()));
+    let impl_id = fixture.insert(None, impl_(Some(display), primitive("u8"), vec![]));
+    let u8_ = rustdoc_types::Primitive {
+        name: "u8".to_string(),
+        impls: vec![impl_id],
+    };
+    fixture.export("u8", ItemEnum::Primitive(u8_));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.impls.len(), 2);
+    assert_eq!(analyze::impl_locality(&krate), (1, 1));
+
+    // Impls are matched by the id of their self type, not by its name.
+    let struct_id = krate.structs[0].id.clone();
+    let foo_ref = Type::BorrowedRef {
+        lifetime: Some("'a".to_string()),
+        mutable: false,
+        type_: Box::new(resolved_path("Foo", &Id(struct_id))),
+    };
+    let external_foo = resolved_path("Foo", &Id("1:2".to_string()));
+    let mut ids = vec![];
+    for for_ in [foo_ref, external_foo] {
+        let display = trait_path("Display", &Id("1:1".to_string()));
+        ids.push(fixture.insert(None, impl_(Some(display), for_, vec![])));
+    }
+    let baz = fixture.reserve_id();
+    fixture.insert_with_id(baz.clone(), Some("Baz"), strukt(ids));
+    fixture.export_id(&baz);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.impls.len(), 4);
+    assert_eq!(analyze::impl_locality(&krate), (2, 2));
+}
+
+#[test]
+fn buckets_types_by_trait_impl_count() {
+    let mut fixture = mixed_fixture();
+    for (name, impl_count) in [("Few", 3), ("Some", 4), ("Many", 11)] {
+        let struct_id = fixture.reserve_id();
+        let impls = (0..impl_count)
+            .map(|i| {
+                let path = trait_path(&format!("Trait{i}"), &Id("1:1".to_string()));
+                let for_ = resolved_path(name, &struct_id);
+                fixture.insert(None, impl_(Some(path), for_, vec![]))
+            })
+            .collect();
+        fixture.insert_with_id(struct_id.clone(), Some(name), strukt(impls));
+        fixture.export_id(&struct_id);
+    }
+
+    let krate = fixture.parse();
+    let histogram = analyze::traits_per_type_histogram(&krate);
+    let expected = [
+        (analyze::Bucket::Zero, 1),
+        (analyze::Bucket::OneToThree, 2),
+        (analyze::Bucket::FourToTen, 1),
+        (analyze::Bucket::MoreThanTen, 1),
+    ];
+    assert_eq!(histogram.into_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn counts_traits_by_method_count() {
+    let mut fixture = mixed_fixture();
+    for (name, method_count) in [("Marker", 0), ("Pair", 2), ("Other", 2)] {
+        let methods = (0..method_count)
+            .map(|i| {
+                let fn_ = ItemEnum::Function(function(vec![], None));
+                fixture.insert(Some(&format!("m{i}")), fn_)
+            })
+            .collect();
+        fixture.export(name, trait_(methods));
+    }
+
+    let krate = fixture.parse();
+    let distribution = krate.trait_method_count_distribution();
+    let expected = [(0, 1), (1, 1), (2, 2)];
+    assert_eq!(distribution.into_iter().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn appends_impls() {
+    let mut krate = mixed_fixture().parse();
+    let mut other = mixed_fixture().parse();
+    assert_eq!(krate.impls.len(), 1);
+    krate.append(&mut other);
+    assert_eq!(krate.impls.len(), 2);
+    assert!(other.impls.is_empty());
+}
+
+#[test]
+fn renders_const_generic_params() {
+    let mut fixture = Fixture::new();
+    let mut strukt = rustdoc_types::Struct {
+        kind: StructKind::Unit,
+        generics: generics(),
+        impls: vec![],
+    };
+    strukt.generics.params.push(GenericParamDef {
+        name: "N".to_string(),
+        kind: GenericParamDefKind::Const {
+            type_: primitive("usize"),
+            default: Some("4".to_string()),
+        },
+    });
+    fixture.export("Foo", ItemEnum::Struct(strukt));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.structs[0].decl, "struct Foo<const N: usize = 4>;");
+}
+
+#[test]
+fn includes_impls_in_the_table() {
+    let krate = mixed_fixture().parse();
+    let table = render_table(krate.to_table());
+    let row = table
+        .lines()
+        .find(|line| line.contains("impl Call for Foo {}"))
+        .expect("impl row");
+    assert!(row.contains("| impl "));
+}
+
+#[test]
+fn labels_table_rows_by_their_kind() {
+    let krate = mixed_fixture().parse();
+    for table in [krate.to_table(), krate.to_expanded_table()] {
+        let table = render_table(table);
+        let row = table
+            .lines()
+            .find(|line| line.contains("enum Bar"))
+            .expect("enum row");
+        assert!(row.contains("| enum "), "{}", row);
+    }
+}
+
+#[test]
+fn skips_synthetic_params_from_impl_trait() {
+    let display = trait_bound(trait_path("Display", &Id("1:1".to_string())));
+    let impl_display = Type::ImplTrait(vec![display.clone()]);
+    let mut fn_ = function(vec![("x", impl_display)], None);
+    fn_.generics.params.push(GenericParamDef {
+        name: "impl Display".to_string(),
+        kind: GenericParamDefKind::Type {
+            bounds: vec![display],
+            default: None,
+            synthetic: true,
+        },
+    });
+    assert_eq!(function_decl("f", fn_), "fn f(x: impl Display) { .. }");
+}
+
+#[test]
+fn expands_methods_below_their_parent() {
+    let krate = mixed_fixture().parse();
+    let table = render_table(krate.to_expanded_table());
+    let lines: Vec<_> = table.lines().collect();
+    let parent = lines
+        .iter()
+        .position(|line| line.contains("fixture::Call"))
+        .expect("trait row");
+    assert!(lines[parent + 2].contains("| method "));
+    assert!(lines[parent + 2].contains("|   call "));
+    assert!(lines.iter().any(|line| line.contains("| fixture::baz ")));
+}
+
+#[test]
+fn summarizes_headline_metrics() {
+    let mut fixture = mixed_fixture();
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![],
+        impls: vec![],
+    });
+    fixture.export("Bits", union);
+    let krate = fixture.parse();
+    let summary = krate.summary_json();
+    let mut keys: Vec<_> = summary.as_object().unwrap().keys().collect();
+    keys.sort();
+    let expected = vec![
+        "counts",
+        "generic_fraction",
+        "schema_version",
+        "stable_fraction",
+        "total_items",
+        "traits_per_adt",
+    ];
+    assert_eq!(keys, expected);
+    assert_eq!(summary["total_items"], 7);
+    assert_eq!(summary["total_items"], krate.iter_items().count());
+    assert_eq!(summary["counts"]["functions"], 2);
+    assert_eq!(summary["counts"]["unions"], 1);
+    assert_eq!(summary["counts"].as_object().unwrap().len(), 11);
+    assert_eq!(summary["traits_per_adt"], 0.5);
+}
+
+#[test]
+fn stats_cover_every_kind_of_item() {
+    let mut fixture = mixed_fixture();
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![],
+        impls: vec![],
+    });
+    let union = fixture.export("Bits", union);
+    fixture.item_mut(&union).attrs.clear();
+    let constant = ItemEnum::Constant(rustdoc_types::Constant {
+        type_: primitive("usize"),
+        expr: "16".to_string(),
+        value: None,
+        is_literal: true,
+    });
+    fixture.export("CAPACITY", constant);
+
+    let krate = fixture.parse();
+    let stats = krate.stats();
+    assert_eq!(stats.unions.total, 1);
+    assert_eq!(stats.unions.unstable, 1);
+    assert_eq!(stats.constants.stable, 1);
+    assert_eq!(
+        stats.stable_fraction,
+        krate.summary_json()["stable_fraction"]
+    );
+    let total: usize = stats
+        .kinds()
+        .iter()
+        .filter(|(kind, _)| *kind != "ADTs")
+        .map(|(_, stats)| stats.total)
+        .sum();
+    assert_eq!(total, krate.iter_items().count());
+}
+
+#[test]
+fn detects_functions_which_never_return() {
+    let mut fixture = Fixture::new();
+    let abort = function(vec![], Some(primitive("never")));
+    fixture.export("abort", ItemEnum::Function(abort));
+    fixture.export("f", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions[0].decl, "fn abort() -> ! { .. }");
+    assert!(krate.functions[0].never_returns);
+    assert!(!krate.functions[1].never_returns);
+    assert_eq!(analyze::count_never_returning(&krate.functions), 1);
+}
+
+#[test]
+fn parses_deprecated_stability() {
+    let mut fixture = Fixture::new();
+    let old = fixture.export("old", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&old).deprecation = Some(rustdoc_types::Deprecation {
+        since: Some("1.2.0".to_string()),
+        note: Some("use `new` instead".to_string()),
+    });
+    fixture.export("new", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(krate.functions[0].stability, Stability::Deprecated);
+    assert!(krate.functions[0].is_deprecated);
+    assert_eq!(krate.functions[1].stability, Stability::Stable);
+    assert!(!krate.functions[1].is_deprecated);
+    let stats = krate.stats().functions;
+    assert_eq!((stats.stable, stats.unstable, stats.deprecated), (2, 0, 1));
+    assert_eq!(krate.counts_by_stability(), (2, 0));
+    assert!(krate.unstable_items().is_empty());
+}
+
+#[test]
+fn renders_higher_ranked_bounds_in_where_clauses() {
+    let f = Type::Generic("F".to_string());
+    let mut fn_trait = trait_path("Fn", &Id("1:1".to_string()));
+    fn_trait.args = Some(Box::new(GenericArgs::Parenthesized {
+        inputs: vec![Type::BorrowedRef {
+            lifetime: Some("'a".to_string()),
+            mutable: false,
+            type_: Box::new(primitive("str")),
+        }],
+        output: None,
+    }));
+    let bound = GenericBound::TraitBound {
+        trait_: fn_trait,
+        generic_params: vec![GenericParamDef {
+            name: "'a".to_string(),
+            kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+        }],
+        modifier: TraitBoundModifier::None,
+    };
+    let mut fn_ = function(vec![("f", f.clone())], None);
+    fn_.generics.params.push(type_param("F", vec![]));
+    fn_.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::BoundPredicate {
+            type_: f,
+            bounds: vec![bound],
+            generic_params: vec![],
+        });
+    assert_eq!(
+        function_decl("call", fn_),
+        "fn call<F>(f: F) where F: for<'a> Fn(&'a str) { .. }"
+    );
+}
+
+#[test]
+fn parses_stability_attributes() {
+    let cases = vec![
+        (r#"#[doc = "use #[stable] here"]"#, Stability::Unstable),
+        (STABLE, Stability::Stable),
+        (
+            r#"#[unstable(feature = "fixture", issue = "none")]"#,
+            Stability::Unstable,
+        ),
+    ];
+    for (attr, expected) in cases {
+        let mut fixture = Fixture::new();
+        let id = fixture.export("f", ItemEnum::Function(function(vec![], None)));
+        fixture.item_mut(&id).attrs = vec![attr.to_string()];
+        assert_eq!(fixture.parse().functions[0].stability, expected, "{attr}");
+    }
+}
+
+#[test]
+fn tags_json_with_the_schema_version() {
+    let json = mixed_fixture().parse().to_json();
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(json["items"].as_array().unwrap().len(), 6);
+    assert_eq!(json["items"][0]["kind"], "trait");
+}
+
+#[test]
+fn renders_const_generic_impls_on_arrays() {
+    let mut fixture = Fixture::new();
+    let array = Type::Array {
+        type_: Box::new(primitive("u8")),
+        len: "N".to_string(),
+    };
+    let trait_ = trait_path("Trait", &Id("1:1".to_string()));
+    let mut impl_item = impl_(Some(trait_), array, vec![]);
+    if let ItemEnum::Impl(impl_) = &mut impl_item {
+        impl_.generics.params.push(GenericParamDef {
+            name: "N".to_string(),
+            kind: GenericParamDefKind::Const {
+                type_: primitive("usize"),
+                default: None,
+            },
+        });
+    }
+    let impl_id = fixture.insert(None, impl_item);
+    let primitive_array = rustdoc_types::Primitive {
+        name: "array".to_string(),
+        impls: vec![impl_id],
+    };
+    fixture.export("array", ItemEnum::Primitive(primitive_array));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.impls[0].decl,
+        "impl<const N: usize> Trait for [u8; N] {}"
+    );
+}
+
+#[test]
+fn finds_methodless_types() {
+    let mut fixture = Fixture::new();
+    let method = fixture.insert(Some("len"), ItemEnum::Function(function(vec![], None)));
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("List", &struct_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![method]));
+    fixture.insert_with_id(struct_id.clone(), Some("List"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+    fixture.export("Point", strukt(vec![]));
+
+    let krate = fixture.parse();
+    let names: Vec<_> = krate.methodless_types().iter().map(|i| &i.name).collect();
+    assert_eq!(names, vec!["Point"]);
+}
+
+#[test]
+fn excludes_derived_impls() {
+    let mut fixture = Fixture::new();
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let debug = trait_path("Debug", &Id("1:1".to_string()));
+    let derived = fixture.insert(None, impl_(Some(debug), for_.clone(), vec![]));
+    let derive = "#[automatically_derived]".to_string();
+    fixture.item_mut(&derived).attrs.push(derive);
+    let display = trait_path("Display", &Id("1:2".to_string()));
+    let manual = fixture.insert(None, impl_(Some(display), for_, vec![]));
+    let foo = strukt(vec![derived.clone(), manual]);
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), foo);
+    fixture.export_id(&struct_id);
+
+    let mut krate = fixture.parse();
+    assert_eq!(krate.impls.len(), 2);
+    let debug = krate.impls.iter().find(|i| i.id == derived.0).unwrap();
+    assert!(debug.derived);
+
+    krate.retain_handwritten_impls();
+    assert_eq!(krate.impls.len(), 1);
+    assert_eq!(krate.impls[0].decl, "impl Display for Foo {}");
+}
+
+#[test]
+fn captures_feature_gates_of_unstable_items() {
+    let cases = vec![
+        (
+            r#"#[unstable(feature = "try_trait_v2", issue = "84277")]"#,
+            Some("84277"),
+        ),
+        (
+            r#"#[unstable(feature = "try_trait_v2", issue = "none")]"#,
+            None,
+        ),
+        (r#"#[unstable(feature = "try_trait_v2")]"#, None),
+    ];
+    for (attr, issue) in cases {
+        let mut fixture = Fixture::new();
+        let id = fixture.export("f", ItemEnum::Function(function(vec![], None)));
+        fixture.item_mut(&id).attrs = vec![attr.to_string()];
+        let krate = fixture.parse();
+        let f = &krate.functions[0];
+        assert_eq!(f.feature.as_deref(), Some("try_trait_v2"), "{attr}");
+        assert_eq!(f.issue.as_deref(), issue, "{attr}");
+    }
+}
+
+#[test]
+fn extracts_the_stabilization_version() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("f", ItemEnum::Function(function(vec![], None)));
+    let stable = r#"#[stable(feature = "is_some_and", since = "1.70.0")]"#;
+    fixture.item_mut(&id).attrs = vec![stable.to_string()];
+
+    let krate = fixture.parse();
+    let f = &krate.functions[0];
+    assert_eq!(f.stability, Stability::Stable);
+    assert_eq!(f.feature.as_deref(), Some("is_some_and"));
+    assert_eq!(f.since.as_deref(), Some("1.70.0"));
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.serialize(f).unwrap();
+    let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert!(csv
+        .lines()
+        .next()
+        .unwrap()
+        .contains(",feature,issue,since,"));
+    assert!(csv.contains(",is_some_and,,1.70.0,"));
+}
+
+#[test]
+fn parses_unions() {
+    let mut fixture = Fixture::new();
+    let method = fixture.insert(Some("as_bits"), ItemEnum::Function(function(vec![], None)));
+    let union_id = fixture.reserve_id();
+    let for_ = resolved_path("Bits", &union_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![method]));
+    let union = ItemEnum::Union(Union {
+        generics: generics(),
+        fields_stripped: false,
+        fields: vec![],
+        impls: vec![impl_id],
+    });
+    fixture.insert_with_id(union_id.clone(), Some("Bits"), union);
+    fixture.export_id(&union_id);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.unions.len(), 1);
+    let union = &krate.unions[0];
+    assert_eq!(union.kind, "union");
+    assert_eq!(union.decl, "union Bits { .. }");
+    assert_eq!(union.fn_count, 1);
+    assert_eq!(krate.functions[0].path, "fixture::Bits");
+}
+
+#[test]
+fn renders_associated_type_bounds_in_dyn_traits() {
+    let mut fixture = Fixture::new();
+    let iterator = Id("1:100".to_string());
+    fixture.add_path(&iterator, &["core", "iter", "Iterator"], ItemKind::Trait);
+    let debug = Id("1:101".to_string());
+    fixture.add_path(&debug, &["core", "fmt", "Debug"], ItemKind::Trait);
+    let binding = TypeBinding {
+        name: "Item".to_string(),
+        args: GenericArgs::AngleBracketed {
+            args: vec![],
+            bindings: vec![],
+        },
+        binding: TypeBindingKind::Constraint(vec![trait_bound(trait_path("Debug", &debug))]),
+    };
+    let iterator = Path {
+        args: Some(Box::new(GenericArgs::AngleBracketed {
+            args: vec![],
+            bindings: vec![binding],
+        })),
+        ..trait_path("Iterator", &iterator)
+    };
+    let dyn_ = Type::DynTrait(rustdoc_types::DynTrait {
+        traits: vec![rustdoc_types::PolyTrait {
+            trait_: iterator,
+            generic_params: vec![],
+        }],
+        lifetime: None,
+    });
+    let arg = Type::BorrowedRef {
+        lifetime: None,
+        mutable: true,
+        type_: Box::new(dyn_),
+    };
+    fixture.export("f", ItemEnum::Function(function(vec![("iter", arg)], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn f(iter: &mut dyn Iterator<Item: Debug>) { .. }"
+    );
+}
+
+#[test]
+fn renders_unit_and_tuple_structs() {
+    let mut fixture = Fixture::new();
+    let phantom = generic_type("PhantomData", vec![Type::Generic("T".to_string())]);
+    let field = fixture.insert(Some("0"), ItemEnum::StructField(phantom));
+    let mut tuple = Struct {
+        kind: StructKind::Tuple(vec![Some(field)]),
+        generics: generics(),
+        impls: vec![],
+    };
+    tuple.generics.params.push(type_param("T", vec![]));
+    fixture.export("Foo", ItemEnum::Struct(tuple));
+
+    let debug = Id("1:100".to_string());
+    fixture.add_path(&debug, &["core", "fmt", "Debug"], ItemKind::Trait);
+    let mut unit = Struct {
+        kind: StructKind::Unit,
+        generics: generics(),
+        impls: vec![],
+    };
+    unit.generics.params.push(type_param("T", vec![]));
+    unit.generics
+        .where_predicates
+        .push(rustdoc_types::WherePredicate::BoundPredicate {
+            type_: Type::Generic("T".to_string()),
+            bounds: vec![trait_bound(trait_path("Debug", &debug))],
+            generic_params: vec![],
+        });
+    fixture.export("Bar", ItemEnum::Struct(unit));
+    fixture.export("Baz", strukt(vec![]));
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate.structs.iter().map(|s| s.decl.as_str()).collect();
+    assert_eq!(
+        decls,
+        vec![
+            "struct Foo<T>(..);",
+            "struct Bar<T> where T: Debug;",
+            "struct Baz;"
+        ]
+    );
+    assert!(krate.structs.iter().take(2).all(|s| s.has_generics));
+}
+
+#[test]
+fn reports_the_location_of_malformed_json() {
+    let err = Crate::from_str("{\n  \"root\": ,\n}").unwrap_err();
+    match &err {
+        ParseError::Json(json) => assert_eq!((json.line(), json.column()), (2, 11)),
+        err => panic!("unexpected error: {}", err),
+    }
+    assert!(err.to_string().contains("line 2 column 11"), "{}", err);
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn reports_mistyped_fields() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("f", ItemEnum::Function(function(vec![], None)));
+    let mut json = serde_json::to_value(&fixture.krate).unwrap();
+    json["index"][&id.0]["crate_id"] = "zero".into();
+    let json = serde_json::to_string(&json).unwrap();
+
+    let err = Crate::from_str(&json).unwrap_err();
+    match &err {
+        ParseError::Json(json) => assert!(json.is_data(), "{}", json),
+        err => panic!("unexpected error: {}", err),
+    }
+    assert!(err.to_string().contains("\"zero\""), "{}", err);
+}
+
+#[test]
+fn reads_from_a_reader() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/alloc.json");
+    let from_reader = Crate::from_reader(std::fs::File::open(path).unwrap()).unwrap();
+    let from_str = Crate::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert!(!from_reader.traits.is_empty());
+    assert_eq!(from_reader, from_str);
+}
+
+#[test]
+fn lists_undocumented_items() {
+    let mut fixture = Fixture::new();
+    let documented = fixture.export("documented", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&documented).docs = Some("Does a thing.".to_string());
+    let blank = fixture.export("blank", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&blank).docs = Some(" \n".to_string());
+    fixture.export("missing", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert!(krate.functions.iter().any(|f| f.is_documented));
+    let mut names: Vec<_> = krate.undocumented().iter().map(|i| &i.name).collect();
+    names.sort();
+    assert_eq!(names, vec!["blank", "missing"]);
+}
+
+#[test]
+fn caches_parsed_crates_until_the_input_changes() {
+    let dir = std::env::temp_dir().join(format!("rustdoc-denormalize-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("fixture.json");
+    let cache = dir.join("fixture.json.cache");
+    let mut fixture = mixed_fixture();
+    std::fs::write(&path, serde_json::to_vec(&fixture.krate).unwrap()).unwrap();
+
+    let krate = Crate::from_path_cached(&path).unwrap();
+    assert_eq!(krate, mixed_fixture().parse());
+    assert!(cache.exists());
+
+    // Tamper with the cached output, so a cache hit is observable.
+    let mut cached: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&cache).unwrap()).unwrap();
+    cached["krate"]["functions"][0]["name"] = "cached".into();
+    std::fs::write(&cache, serde_json::to_vec(&cached).unwrap()).unwrap();
+    let krate = Crate::from_path_cached(&path).unwrap();
+    assert_eq!(krate.functions[0].name, "cached");
+
+    fixture.export("qux", ItemEnum::Function(function(vec![], None)));
+    std::fs::write(&path, serde_json::to_vec(&fixture.krate).unwrap()).unwrap();
+    let krate = Crate::from_path_cached(&path).unwrap();
+    assert_eq!(krate, fixture.parse());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parses_crates_whose_cache_cant_be_written() {
+    let dir = std::env::temp_dir().join(format!(
+        "rustdoc-denormalize-unwritable-{}",
+        std::process::id()
+    ));
+    // A directory in place of the cache file makes writing it fail.
+    std::fs::create_dir_all(dir.join("fixture.json.cache")).unwrap();
+    let path = dir.join("fixture.json");
+    let fixture = mixed_fixture();
+    std::fs::write(&path, serde_json::to_vec(&fixture.krate).unwrap()).unwrap();
+
+    let krate = Crate::from_path_cached(&path).unwrap();
+    assert_eq!(krate, fixture.parse());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parses_constants_and_statics() {
+    let mut fixture = Fixture::new();
+    let constant = ItemEnum::Constant(rustdoc_types::Constant {
+        type_: primitive("usize"),
+        expr: "usize::MAX".to_string(),
+        value: Some("18_446_744_073_709_551_615usize".to_string()),
+        is_literal: false,
+    });
+    fixture.export("MAX", constant);
+    let static_ = ItemEnum::Static(rustdoc_types::Static {
+        type_: Type::BorrowedRef {
+            lifetime: Some("'static".to_string()),
+            mutable: false,
+            type_: Box::new(primitive("str")),
+        },
+        mutable: true,
+        expr: "\"hello\"".to_string(),
+    });
+    fixture.export("GREETING", static_);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.constants[0].kind, "const");
+    assert_eq!(krate.constants[0].decl, "const MAX: usize = usize::MAX;");
+    assert_eq!(krate.statics[0].kind, "static");
+    assert_eq!(
+        krate.statics[0].decl,
+        "static mut GREETING: &'static str = \"hello\";"
+    );
+    assert_eq!(krate.iter_items().count(), 2);
+}
+
+#[test]
+fn parses_result_type_aliases() {
+    let mut fixture = Fixture::new();
+    let error = resolved_path("Error", &Id("0:100".to_string()));
+    let result = generic_type("Result", vec![Type::Generic("T".to_string()), error]);
+    let alias = rustdoc_types::Typedef {
+        type_: result,
+        generics: Generics {
+            params: vec![type_param("T", vec![])],
+            where_predicates: vec![],
+        },
+    };
+    fixture.export("Result", ItemEnum::Typedef(alias));
+
+    let krate = fixture.parse();
+    let alias = &krate.type_aliases[0];
+    assert_eq!(alias.kind, "type");
+    assert_eq!(alias.path, "fixture");
+    assert_eq!(alias.decl, "type Result<T> = Result<T, Error>;");
+    assert_eq!(alias.max_generic_depth, 1);
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.serialize(alias).unwrap();
+    let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert!(csv.contains("type Result<T> = Result<T, Error>;"));
+}
+
+#[test]
+fn renders_maybe_const_bounds_in_either_syntax() {
+    let mut fixture = Fixture::new();
+    let drop = GenericBound::TraitBound {
+        trait_: trait_path("Drop", &Id("1:1".to_string())),
+        generic_params: vec![],
+        modifier: TraitBoundModifier::MaybeConst,
+    };
+    let mut fn_ = function(vec![("t", Type::Generic("T".to_string()))], None);
+    fn_.generics.params.push(type_param("T", vec![drop]));
+    fn_.header.const_ = true;
+    fixture.export("destroy", ItemEnum::Function(fn_));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "const fn destroy<T: ~const Drop>(t: T) { .. }"
+    );
+
+    let options = Options {
+        bracketed_const: true,
+        ..Options::default()
+    };
+    let krate = fixture.parse_with_options(options);
+    assert_eq!(
+        krate.functions[0].decl,
+        "const fn destroy<T: [const] Drop>(t: T) { .. }"
+    );
+}
+
+#[test]
+fn escapes_keywords_as_raw_identifiers() {
+    let mut fixture = Fixture::new();
+    let args = vec![
+        ("type", primitive("u8")),
+        ("self", Type::Generic("Self".to_string())),
+    ];
+    fixture.export("match", ItemEnum::Function(function(args, None)));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn r#match(r#type: u8, self) { .. }"
+    );
+}
+
+#[test]
+fn sorts_items_within_their_module() {
+    let mut fixture = mixed_fixture();
+    fixture.export("Alpha", strukt(vec![]));
+    fixture.export("aaa", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    let items: Vec<_> = krate
+        .sort_within_module()
+        .iter()
+        .map(|item| format!("{} {}::{}", item.kind, item.path, item.name))
+        .collect();
+    assert_eq!(
+        items,
+        vec![
+            "struct fixture::Alpha",
+            "struct fixture::Foo",
+            "enum fixture::Bar",
+            "trait fixture::Call",
+            "function fixture::aaa",
+            "function fixture::baz",
+            "impl fixture::Call",
+            "function fixture::Call::call",
+        ]
+    );
+}
+
+#[test]
+fn extracts_the_first_line_of_docs() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("f", ItemEnum::Function(function(vec![], None)));
+    let docs = "\nReturns the **first** `as_str` of a __slice__.\n\nMore details *here*.";
+    fixture.item_mut(&id).docs = Some(docs.to_string());
+    fixture.export("g", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].doc_summary.as_deref(),
+        Some("Returns the first `as_str` of a slice.")
+    );
+    assert_eq!(krate.functions[1].doc_summary, None);
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.serialize(&krate.functions[0]).unwrap();
+    let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert!(csv.lines().next().unwrap().contains(",doc_summary,"));
+    assert!(csv.contains("Returns the first `as_str` of a slice."));
+}
+
+#[test]
+fn shows_constants_in_the_table() {
+    let mut fixture = Fixture::new();
+    let constant = ItemEnum::Constant(rustdoc_types::Constant {
+        type_: primitive("usize"),
+        expr: "_".to_string(),
+        value: Some("64usize".to_string()),
+        is_literal: false,
+    });
+    fixture.export("MAX", constant);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.constants[0].decl, "const MAX: usize = 64usize;");
+    for table in [krate.to_table(), krate.to_expanded_table()] {
+        let table = render_table(table);
+        let row = table
+            .lines()
+            .find(|line| line.contains("const MAX: usize = 64usize;"))
+            .expect("constant row");
+        assert!(row.contains("| const "));
+        assert!(row.contains("fixture::MAX"));
+    }
+}
+
+#[test]
+fn walks_nested_modules_from_the_root() {
+    let mut fixture = Fixture::new();
+    let deep = fixture.insert(Some("deep"), ItemEnum::Function(function(vec![], None)));
+    let module = |items| {
+        ItemEnum::Module(Module {
+            is_crate: false,
+            items,
+            is_stripped: false,
+        })
+    };
+    let inner = fixture.insert(Some("inner"), module(vec![deep]));
+    let shallow = fixture.insert(Some("shallow"), ItemEnum::Function(function(vec![], None)));
+    fixture.export("outer", module(vec![inner, shallow]));
+
+    // Neither module has an entry in `paths`, so their paths come from the tree.
+    let krate = fixture.parse();
+    let paths: Vec<_> = krate
+        .functions
+        .iter()
+        .map(|f| format!("{}::{}", f.path, f.name))
+        .collect();
+    assert_eq!(
+        paths,
+        vec!["fixture::outer::inner::deep", "fixture::outer::shallow"]
+    );
+}
+
+#[test]
+fn renders_associated_consts_of_trait_impls() {
+    let mut fixture = Fixture::new();
+    let trait_id = Id("1:100".to_string());
+    fixture.add_path(&trait_id, &["fixture", "Identify"], ItemKind::Trait);
+    let id = ItemEnum::AssocConst {
+        type_: primitive("u32"),
+        default: Some("3".to_string()),
+    };
+    let id = fixture.insert(Some("ID"), id);
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let path = trait_path("Identify", &trait_id);
+    let impl_id = fixture.insert(None, impl_(Some(path), for_, vec![id]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    let impl_ = &krate.impls[0];
+    assert_eq!(impl_.decl, "impl Identify for Foo { const ID: u32 = 3; }");
+    assert_eq!(impl_.assoc_const_count, 1);
+    assert!(krate.assoc_items.is_empty());
+}
+
+#[test]
+fn shows_generic_unions_in_the_table() {
+    let mut fixture = Fixture::new();
+    let mut generics = generics();
+    generics.params.push(type_param("T", vec![]));
+    let union = ItemEnum::Union(Union {
+        generics,
+        fields_stripped: true,
+        fields: vec![],
+        impls: vec![],
+    });
+    fixture.export("MaybeUninit", union);
+
+    let krate = fixture.parse();
+    let union = &krate.unions[0];
+    assert!(union.has_generics);
+    assert_eq!(union.decl, "union MaybeUninit<T> { .. }");
+    for table in [krate.to_table(), krate.to_expanded_table()] {
+        let table = render_table(table);
+        let row = table
+            .lines()
+            .find(|line| line.contains("union MaybeUninit<T> { .. }"))
+            .expect("union row");
+        assert!(row.contains("| union "));
+        assert!(row.contains("fixture::MaybeUninit"));
+    }
+}
+
+#[test]
+fn counts_struct_fields() {
+    let mut fixture = Fixture::new();
+    let fields = ["x", "y", "z"]
+        .iter()
+        .map(|name| fixture.insert(Some(name), ItemEnum::StructField(primitive("f32"))))
+        .collect();
+    let plain = Struct {
+        kind: StructKind::Plain {
+            fields,
+            fields_stripped: false,
+        },
+        generics: generics(),
+        impls: vec![],
+    };
+    fixture.export("Point", ItemEnum::Struct(plain));
+    let tuple = Struct {
+        kind: StructKind::Tuple(vec![None, None]),
+        generics: generics(),
+        impls: vec![],
+    };
+    fixture.export("Pair", ItemEnum::Struct(tuple));
+    fixture.export("Unit", strukt(vec![]));
+
+    let krate = fixture.parse();
+    let counts: Vec<_> = krate
+        .structs
+        .iter()
+        .map(|s| (s.name.as_str(), s.field_count))
+        .collect();
+    assert_eq!(counts, vec![("Point", 3), ("Pair", 2), ("Unit", 0)]);
+}
+
+#[test]
+fn renders_const_fns_in_every_position() {
+    let const_fn = || {
+        let mut fn_ = function(vec![], Some(primitive("usize")));
+        fn_.header.const_ = true;
+        ItemEnum::Function(fn_)
+    };
+    let mut fixture = Fixture::new();
+    fixture.export("free", const_fn());
+    let method = fixture.insert(Some("inherent"), const_fn());
+    let struct_id = fixture.reserve_id();
+    let for_ = resolved_path("Foo", &struct_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![method]));
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(vec![impl_id]));
+    fixture.export_id(&struct_id);
+    let method = fixture.insert(Some("provided"), const_fn());
+    fixture.export("Len", trait_(vec![method]));
+
+    let krate = fixture.parse();
+    let decls: Vec<_> = krate
+        .functions
+        .iter()
+        .map(|f| (f.path.as_str(), f.decl.as_str(), f.is_const))
+        .collect();
+    assert_eq!(
+        decls,
+        vec![
+            ("fixture", "const fn free() -> usize { .. }", true),
+            ("fixture::Foo", "const fn inherent() -> usize { .. }", true),
+            ("fixture::Len", "const fn provided() -> usize { .. }", true),
+        ]
+    );
+}
+
+#[test]
+fn flags_impls_generic_only_over_lifetimes() {
+    let mut fixture = Fixture::new();
+    let display = Id("1:100".to_string());
+    fixture.add_path(&display, &["core", "fmt", "Display"], ItemKind::Trait);
+    let debug = Id("1:101".to_string());
+    fixture.add_path(&debug, &["core", "fmt", "Debug"], ItemKind::Trait);
+    let struct_id = fixture.reserve_id();
+    let lifetime = GenericParamDef {
+        name: "'a".to_string(),
+        kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+    };
+    let mut impls = vec![];
+    for (trait_, params) in [
+        (trait_path("Display", &display), vec![lifetime.clone()]),
+        (
+            trait_path("Debug", &debug),
+            vec![lifetime, type_param("T", vec![])],
+        ),
+    ] {
+        let mut impl_ = impl_(Some(trait_), resolved_path("Foo", &struct_id), vec![]);
+        if let ItemEnum::Impl(impl_) = &mut impl_ {
+            impl_.generics.params = params;
+        }
+        impls.push(fixture.insert(None, impl_));
+    }
+    fixture.insert_with_id(struct_id.clone(), Some("Foo"), strukt(impls));
+    fixture.export_id(&struct_id);
+
+    let krate = fixture.parse();
+    let flags: Vec<_> = krate
+        .impls
+        .iter()
+        .map(|i| (i.name.as_str(), i.has_generics, i.lifetime_only_generics))
+        .collect();
+    assert_eq!(
+        flags,
+        vec![("Display", false, true), ("Debug", true, false)]
+    );
+}
+
+#[test]
+fn parses_declarative_and_proc_macros() {
+    let mut fixture = Fixture::new();
+    let source = "macro_rules! square {\n    ($x:expr) => { ... };\n}";
+    fixture.export("square", ItemEnum::Macro(source.to_string()));
+    let derive = rustdoc_types::ProcMacro {
+        kind: rustdoc_types::MacroKind::Derive,
+        helpers: vec!["serde".to_string()],
+    };
+    fixture.export("Serialize", ItemEnum::ProcMacro(derive));
+
+    let krate = fixture.parse();
+    let macros: Vec<_> = krate
+        .macros
+        .iter()
+        .map(|m| (m.kind, m.name.as_str(), m.decl.as_str()))
+        .collect();
+    assert_eq!(
+        macros,
+        vec![
+            ("macro", "square", source),
+            ("proc_derive", "Serialize", "#[derive(Serialize)]"),
+        ]
+    );
+
+    let table = render_table(krate.to_table());
+    assert!(table.lines().any(|line| line.contains("| proc_derive ")));
+}
+
+#[test]
+fn counts_enum_variants() {
+    let mut fixture = Fixture::new();
+    let mut variant = |name: &str, kind: VariantKind| {
+        let variant = ItemEnum::Variant(Variant {
+            kind,
+            discriminant: None,
+        });
+        fixture.insert(Some(name), variant)
+    };
+    let struct_variant = VariantKind::Struct {
+        fields: vec![],
+        fields_stripped: false,
+    };
+    let variants = vec![
+        variant("Empty", VariantKind::Plain),
+        variant("Eof", VariantKind::Plain),
+        variant("At", struct_variant),
+    ];
+    let method = fixture.insert(Some("is_empty"), ItemEnum::Function(function(vec![], None)));
+    let enum_id = fixture.reserve_id();
+    let for_ = resolved_path("Error", &enum_id);
+    let impl_id = fixture.insert(None, impl_(None, for_, vec![method]));
+    fixture.insert_with_id(
+        enum_id.clone(),
+        Some("Error"),
+        enum_(variants, vec![impl_id]),
+    );
+    fixture.export_id(&enum_id);
+
+    let krate = fixture.parse();
+    assert_eq!(krate.enums[0].variant_count, 3);
+    assert_eq!(krate.enums[0].fn_count, 1);
+}
+
+#[test]
+fn writes_csv_to_a_file() {
+    let krate = mixed_fixture().parse();
+    let path = std::env::temp_dir().join(format!("rustdoc-denormalize-{}.csv", std::process::id()));
+    krate
+        .write_csv(std::fs::File::create(&path).unwrap())
+        .unwrap();
+
+    let mut reader = csv::Reader::from_path(&path).unwrap();
+    assert_eq!(&reader.headers().unwrap()[0], "kind");
+    let rows: Vec<_> = reader
+        .records()
+        .map(|record| {
+            let record = record.unwrap();
+            format!("{} {}", &record[0], &record[2])
+        })
+        .collect();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            "struct Foo",
+            "enum Bar",
+            "trait Call",
+            "function call",
+            "function baz",
+            "impl Call"
+        ]
+    );
+}
+
+#[test]
+fn renders_associated_items_of_traits() {
+    let mut fixture = Fixture::new();
+    let item = ItemEnum::AssocType {
+        generics: generics(),
+        bounds: vec![],
+        default: None,
+    };
+    let item = fixture.insert(Some("Item"), item);
+    let cap = ItemEnum::AssocConst {
+        type_: primitive("usize"),
+        default: Some("16".to_string()),
+    };
+    let cap = fixture.insert(Some("CAPACITY"), cap);
+    let next = fixture.insert(Some("next"), ItemEnum::Function(function(vec![], None)));
+    fixture.export("Buffer", trait_(vec![item, cap, next]));
+
+    let krate = fixture.parse();
+    let buffer = &krate.traits[0];
+    assert_eq!(
+        buffer.decl,
+        "trait Buffer { type Item; const CAPACITY: usize = 16; }"
+    );
+    assert_eq!(buffer.assoc_count, 2);
+    assert_eq!(buffer.fn_count, 1);
+}
+
+#[test]
+fn renders_where_clauses_of_traits_with_associated_items() {
+    let mut fixture = Fixture::new();
+    let item = ItemEnum::AssocType {
+        generics: generics(),
+        bounds: vec![],
+        default: None,
+    };
+    let item = fixture.insert(Some("Item"), item);
+    let mut trait_ = trait_(vec![item]);
+    if let ItemEnum::Trait(trait_) = &mut trait_ {
+        let clone = trait_bound(trait_path("Clone", &Id("1:1".to_string())));
+        trait_.generics.params.push(type_param("T", vec![]));
+        trait_
+            .generics
+            .where_predicates
+            .push(rustdoc_types::WherePredicate::BoundPredicate {
+                type_: Type::Generic("T".to_string()),
+                bounds: vec![clone],
+                generic_params: vec![],
+            });
+    }
+    fixture.export("Buffer", trait_);
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.traits[0].decl,
+        "trait Buffer<T> where T: Clone { type Item; }"
+    );
+}
+
+#[test]
+fn renders_impl_trait_returns_in_traits() {
+    let mut fixture = Fixture::new();
+    let binding = TypeBinding {
+        name: "Item".to_string(),
+        args: GenericArgs::AngleBracketed {
+            args: vec![],
+            bindings: vec![],
+        },
+        binding: TypeBindingKind::Equality(rustdoc_types::Term::Type(primitive("u32"))),
+    };
+    let iterator = Path {
+        args: Some(Box::new(GenericArgs::AngleBracketed {
+            args: vec![],
+            bindings: vec![binding],
+        })),
+        ..trait_path("Iterator", &Id("1:100".to_string()))
+    };
+    let output = Type::ImplTrait(vec![trait_bound(iterator)]);
+    let receiver = Type::BorrowedRef {
+        lifetime: None,
+        mutable: false,
+        type_: Box::new(Type::Generic("Self".to_string())),
+    };
+    let mut keys = function(vec![("self", receiver)], Some(output));
+    keys.has_body = false;
+    let keys = fixture.insert(Some("keys"), ItemEnum::Function(keys));
+    fixture.export("Map", trait_(vec![keys]));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].decl,
+        "fn keys(&self) -> impl Iterator<Item = u32>;"
+    );
+}
+
+#[test]
+fn truncates_docs_to_their_first_paragraph() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("Vec", strukt(vec![]));
+    let docs = "A contiguous growable array type, written as `Vec<T>`, short for\n\
+                'vector'.\n\n# Examples\n\nMore text.";
+    fixture.item_mut(&id).docs = Some(docs.to_string());
+    fixture.export("f", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.structs[0].docs.as_deref(),
+        Some("A contiguous growable array type, written as `Vec<T>`, short for\n'vector'.")
+    );
+    assert_eq!(
+        krate.structs[0].doc_summary.as_deref(),
+        Some("A contiguous growable array type, written as `Vec<T>`, short for")
+    );
+    assert_eq!(krate.functions[0].docs, None);
+}
+
+#[test]
+fn keeps_emphasis_markers_inside_code_spans() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("mul", ItemEnum::Function(function(vec![], None)));
+    let docs = "Computes `self * rhs` as a *raw* `*const __m128`, __wrapping__.";
+    fixture.item_mut(&id).docs = Some(docs.to_string());
+
+    let krate = fixture.parse();
+    assert_eq!(
+        krate.functions[0].doc_summary.as_deref(),
+        Some("Computes `self * rhs` as a raw `*const __m128`, wrapping.")
+    );
+}
+
+#[test]
+fn writes_items_to_sqlite() {
+    let krate = mixed_fixture().parse();
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    krate.write_sqlite(&conn).unwrap();
+
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM functions"), 2);
+    assert_eq!(count("SELECT COUNT(*) FROM traits WHERE fn_count = 1"), 1);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM impls WHERE for_type = 'Foo'"),
+        1
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM macros"), 0);
+
+    // Writing again replaces the tables instead of failing on them.
+    krate.write_sqlite(&conn).unwrap();
+    assert_eq!(count("SELECT COUNT(*) FROM functions"), 2);
+
+    // An empty crate still gets every table, with every column.
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    Fixture::new().parse().write_sqlite(&conn).unwrap();
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM traits WHERE fn_count = 1"), 0);
+    assert_eq!(count("SELECT COUNT(*) FROM macros"), 0);
+}
+
+#[test]
+fn tags_crate_visible_items() {
+    let mut fixture = Fixture::new();
+    let id = fixture.export("helper", ItemEnum::Function(function(vec![], None)));
+    fixture.item_mut(&id).visibility = Visibility::Crate;
+    fixture.export("api", ItemEnum::Function(function(vec![], None)));
+
+    let krate = fixture.parse();
+    let helper = &krate.functions[0];
+    assert_eq!(helper.visibility, rustdoc_denormalize::Visibility::Crate);
+    assert!(!helper.visibility.is_public());
+    assert_eq!(helper.visibility.to_string(), "pub(crate)");
+    assert!(krate.functions[1].visibility.is_public());
+}